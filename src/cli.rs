@@ -1,5 +1,13 @@
-use super::status_constraints::{parse_status_constraints, StatusConstraint};
+use super::delay::{parse_delay, Delay};
+use super::options::rerun::FinalStatus;
+use super::rate_limit::{parse_rate_limit, RateLimit};
+use super::rerun_policy::{parse_rerun_group, parse_restart_policy, RerunGroup};
+use super::signal::{
+    parse_catchable_signal, parse_signal, parse_signal_or_zero, parse_signal_translation, Signal,
+};
+use super::status_constraints::{parse_status_constraints, ConstraintExpression, StatusCode};
 use clap::Clap;
+use std::{num::NonZeroU32, path::PathBuf, time::Duration};
 
 #[derive(Clap, Debug)]
 #[clap(
@@ -11,24 +19,309 @@ pub struct Opts {
     #[clap(
         long, short,
         require_equals = true, min_values = 0,
-        default_missing_value = "!0", parse(try_from_str = parse_status_constraints),
+        default_missing_value = "!0", parse(try_from_str = parse_rerun_group),
         value_name = "status",
         about = "After the process exited with a matching status code, rerun it",
         long_about = "\
             After the process exited with a matching status code (by default anything but 0 matches), rerun it.\n\
             Status can also be one or more ranges given as '[!][{>|<}][=]<status>...', which must all match the exit status of the process.\n\
-            Option can be repeated, in which case one value matching suffices to cause a rerun.\n\n\
+            Status can also be a range like '1..=10' or '1..10' (excluding 10), or a list like '1,2,75'.\n\
+            Status can also be '&<mask>' to test that all bits of the mask are set, like '&4' for bit 2. After another constraint, '&' always combines constraints, so test bits there with '& &<mask>' like '!0 & &4'.\n\
+            Status can also be a signal name like 'INT', 'SIGINT' or 'sig:2' to match a process terminated by that signal, but not one exiting with 128 + signal number.\n\
+            Keywords match how the process ended: 'exited', 'signal' (terminated by a signal), 'coredump', 'timeout' and 'spawn-failed' (status 127).\n\
+            A process that failed to start only matches 'spawn-failed', not any status code or other keyword, whether negated or not, so neither '!0' nor 'failure' match it.\n\
+            Aliases are 'success' ('exited & 0'), 'failure' ('!success') and 'abort' (terminated by a signal other than SIGHUP, SIGINT, SIGTERM or SIGPIPE).\n\
+            Constraint sets are given as '@<name>', like '@curl-transient', '@wget-transient', '@rsync-partial', '@rsync-transient', '@ssh-transient' or '@git-transient'.\n\
+            More sets can be defined as lines of '<name> = <constraints>' in the file at $PRCS_CONSTRAINT_SETS, or else $XDG_CONFIG_HOME/prcs/constraint-sets.\n\
+            These can be combined with '&' (also implied by whitespace), '|', '!' and parentheses, like '(>=1 & <=5) | signal & SIGTERM'.\n\
+            Option can be repeated, in which case one value matching suffices to cause a rerun.\n\
+            Status can be followed by '; tries=<tries>' and '; delay=<delay>' to override --max-tries and --rerun-delay for reruns caused by this option.\n\
+            These tries and delays are counted separately for each option, while --max-tries still limits the total number of runs.\n\
+            The process is passed the environment variables PRCS_ATTEMPT (number of the run, starting at 1), PRCS_PREVIOUS_STATUS (unset on the first run), PRCS_MAX_TRIES (unset without --max-tries) and PRCS_ELAPSED_MS (milliseconds since the first run started).\n\n\
             Examples\n\
             Retry mycmd until it succeeds (exits with 0):\n\
             prcs -r mycmd\n\
-            Rerun mycmd while it is successful or terminated by a signal (status > 128) other than program interrupt (SIGINT, status 130):\n\
-            prcs -r=0 -r='>128!130' mycmd\n\n\
+            Rerun mycmd while it is successful or terminated by a signal other than program interrupt (SIGINT):\n\
+            prcs -r=0 -r='signal !SIGINT' mycmd\n\
+            Retry mycmd up to 10 times with 30 seconds delay on temporary failure (status 75), and up to 2 times immediately when terminated by a signal:\n\
+            prcs -r='75; tries=10; delay=30s' -r='signal; tries=2' mycmd\n\n\
             If --stall is also specified, only stall when not rerunning anymore.\n\
             Rerun mycmd until it succeeds, then do nothing until interrupted:\n\
             prcs -s -r mycmd\n\
         "
     )]
-    pub rerun: Option<Vec<Vec<StatusConstraint>>>,
+    pub rerun: Option<Vec<RerunGroup>>,
+    #[clap(
+        long,
+        parse(try_from_str = parse_restart_policy),
+        possible_values = &["always", "on-success", "on-failure", "on-abnormal", "on-abort"],
+        value_name = "policy",
+        about = "Rerun the process according to a preset, like the Restart= setting of systemd services",
+        long_about = "\
+            Rerun the process according to a preset, like the Restart= setting of systemd services.\n\
+            always: rerun after any outcome\n\
+            on-success: rerun after status 0 or termination by SIGHUP, SIGINT, SIGTERM or SIGPIPE ('success | signal & (HUP | INT | TERM | PIPE)')\n\
+            on-failure: rerun after a nonzero status, an abort, a timeout or failing to start ('exited !0 | abort | timeout | spawn-failed')\n\
+            on-abnormal: rerun after an abort or a timeout ('abort | timeout')\n\
+            on-abort: rerun after termination by a signal other than SIGHUP, SIGINT, SIGTERM or SIGPIPE ('abort')\n\
+            Applies after any --rerun options, and is limited by --max-tries and --rerun-delay like them.\n\n\
+            Examples\n\
+            Restart myserver when it fails, at most every 5 seconds:\n\
+            prcs --restart=on-failure --rerun-delay=5s myserver\n\
+        "
+    )]
+    pub restart: Option<RerunGroup>,
+    #[clap(
+        long,
+        short,
+        value_name = "tries",
+        about = "Run the process at most this many times when rerunning",
+        long_about = "\
+            Run the process at most this many times when rerunning, including the first run.\n\
+            Once the limit is reached, the status of the last run is used as if no rerun had been requested.\n\
+            Has no effect without --rerun.\n\n\
+            Examples\n\
+            Try mycmd up to 3 times until it succeeds:\n\
+            prcs -r --max-tries=3 mycmd\n\
+        "
+    )]
+    pub max_tries: Option<NonZeroU32>,
+    #[clap(
+        long,
+        parse(try_from_str = parse_delay),
+        value_name = "delay",
+        about = "Wait before rerunning the process",
+        long_about = "\
+            Wait before rerunning the process.\n\
+            Delay is given as '<initial>[{+<step>|*<factor>}][..<max>][~<jitter>]', where durations are written like '1s', '500ms' or '1m 30s'.\n\
+            With '+<step>', the delay grows linearly by step with every rerun, with '*<factor>', it is multiplied by factor with every rerun.\n\
+            The delay never grows beyond max, and a random duration of up to jitter is added to every delay.\n\
+            Has no effect without --rerun.\n\n\
+            Examples\n\
+            Retry mycmd until it succeeds, waiting 5 seconds between tries:\n\
+            prcs -r --rerun-delay=5s mycmd\n\
+            Retry mycmd until it succeeds, waiting 1, 2, 4, ... seconds but no longer than a minute, plus up to 500ms:\n\
+            prcs -r --rerun-delay='1s*2..1m~500ms' mycmd\n\
+        "
+    )]
+    pub rerun_delay: Option<Delay>,
+    #[clap(
+        long,
+        parse(try_from_str = humantime::parse_duration),
+        value_name = "duration",
+        about = "Only rerun the process within this duration after it was first started",
+        long_about = "\
+            Only rerun the process within this duration after it was first started.\n\
+            Once the duration has passed, or would pass while waiting for --rerun-delay, the status of the last run is used as if no rerun had been requested.\n\
+            Duration is written like '30s', '10m' or '1h 30m'.\n\
+            Has no effect without --rerun.\n\n\
+            Examples\n\
+            Retry mycmd until it succeeds, but give up after 10 minutes:\n\
+            prcs -r --rerun-for=10m mycmd\n\
+        "
+    )]
+    pub rerun_for: Option<Duration>,
+    #[clap(
+        long,
+        parse(try_from_str = parse_rate_limit),
+        value_name = "reruns/duration",
+        about = "Give up rerunning if the process is rerun too often within a duration",
+        long_about = "\
+            Give up rerunning if the process would be rerun more than the given number of times within the given duration, exiting with status 125.\n\
+            With --rerun-limit-cooldown, wait instead of giving up.\n\
+            Has no effect without --rerun.\n\n\
+            Examples\n\
+            Restart myserver whenever it exits, unless it exits more than 5 times within 10 seconds:\n\
+            prcs -r=>=0 --rerun-limit=5/10s myserver\n\
+        "
+    )]
+    pub rerun_limit: Option<RateLimit>,
+    #[clap(
+        long,
+        parse(try_from_str = humantime::parse_duration),
+        requires = "rerun-limit",
+        value_name = "duration",
+        about = "Wait instead of giving up when exceeding --rerun-limit",
+        long_about = "\
+            Wait for this duration instead of giving up when exceeding --rerun-limit, then continue rerunning.\n\n\
+            Examples\n\
+            Restart myserver whenever it exits, pausing for a minute if it exits more than 5 times within 10 seconds:\n\
+            prcs -r=>=0 --rerun-limit=5/10s --rerun-limit-cooldown=1m myserver\n\
+        "
+    )]
+    pub rerun_limit_cooldown: Option<Duration>,
+    #[clap(
+        long,
+        parse(try_from_str = humantime::parse_duration),
+        value_name = "duration",
+        about = "Consider the process stable if it ran at least this long, resetting --max-tries and --rerun-delay",
+        long_about = "\
+            Consider the process stable if it ran at least this long before exiting.\n\
+            After a stable run, rerunning starts over as if it was the first run, so --max-tries counts from the stable run and --rerun-delay uses its initial delay again.\n\
+            Has no effect without --rerun.\n\n\
+            Examples\n\
+            Restart myserver with exponential backoff, starting over once it ran for an hour:\n\
+            prcs -r=>=0 --rerun-delay='1s*2..5m' --rerun-stable-after=1h myserver\n\
+        "
+    )]
+    pub rerun_stable_after: Option<Duration>,
+    #[clap(
+        long,
+        arg_enum,
+        default_value = "last",
+        value_name = "run",
+        about = "Which run to take the exit status from when rerunning",
+        long_about = "\
+            Which run to take the exit status from when rerunning.\n\
+            'last' and 'first' take the status of the last or first run.\n\
+            'worst' and 'best' take the highest or lowest status, where 0 is lower than any other status.\n\
+            'any-success' takes 0 if any run was successful and the status of the last run otherwise.\n\
+            Has no effect without --rerun.\n\n\
+            Examples\n\
+            Run mytest 5 times and fail if any run failed:\n\
+            prcs -r=>=0 --max-tries=5 --exit-status=worst mytest\n\
+        "
+    )]
+    pub exit_status: FinalStatus,
+    #[clap(
+        long, short,
+        parse(try_from_str = humantime::parse_duration),
+        value_name = "duration",
+        about = "Stop the process if it runs longer than this duration",
+        long_about = "\
+            Stop the process if it runs longer than this duration by sending it --timeout-signal, and use status 124 for it.\n\
+            If the process is still running --kill-after the signal was sent, kill it.\n\
+            When rerunning, the duration applies to every single run.\n\n\
+            Examples\n\
+            Run mycmd, stopping it after 30 seconds:\n\
+            prcs --timeout=30s mycmd\n\
+            Run mycmd for up to 30 seconds, retrying it if it times out:\n\
+            prcs -r=124 --timeout=30s mycmd\n\
+        "
+    )]
+    pub timeout: Option<Duration>,
+    #[clap(
+        long,
+        parse(try_from_str = humantime::parse_duration),
+        value_name = "duration",
+        about = "Stop the process if it does not print any output for this long",
+        long_about = "\
+            Stop the process if it does not print anything to stdout or stderr for this long, like --timeout.\n\
+            The output of the process is passed through prcs to notice when it was printed.\n\
+            Exit status is 124 if the process was stopped, which can be matched by --rerun.\n\n\
+            Examples\n\
+            Run mycmd, restarting it when it hangs without printing anything for 5 minutes:\n\
+            prcs -r=124 --idle-timeout=5m mycmd\n\
+        "
+    )]
+    pub idle_timeout: Option<Duration>,
+    #[clap(
+        long,
+        default_value = "TERM",
+        parse(try_from_str = parse_signal),
+        value_name = "signal",
+        about = "Signal to send to the process when it times out",
+        long_about = "\
+            Signal to send to the process when it runs longer than --timeout, given by name like 'TERM' or 'SIGTERM', or by number.\n\
+            On platforms without signals, the process is killed regardless of this option.\n\n\
+            Examples\n\
+            Run mycmd, interrupting it after 30 seconds:\n\
+            prcs --timeout=30s --timeout-signal=INT mycmd\n\
+        "
+    )]
+    pub timeout_signal: Signal,
+    #[clap(
+        long,
+        default_value = "10s",
+        parse(try_from_str = humantime::parse_duration),
+        value_name = "duration",
+        about = "Kill the process if it is still running this long after it timed out",
+        long_about = "\
+            Kill the process if it is still running this long after --timeout-signal was sent to it.\n\n\
+            Examples\n\
+            Run mycmd, stopping it after 30 seconds and killing it if it does not stop within 5 more seconds:\n\
+            prcs --timeout=30s --kill-after=5s mycmd\n\
+        "
+    )]
+    pub kill_after: Duration,
+    #[clap(
+        long,
+        require_equals = true, number_of_values = 1,
+        parse(try_from_str = parse_signal_translation),
+        value_name = "signal:signal",
+        about = "When prcs receives the first signal, send the second signal to the process instead",
+        long_about = "\
+            When prcs receives the first signal, send the second signal to the process instead of forwarding the first signal.\n\
+            Signals are given by name like 'TERM' or 'SIGTERM', or by number. Signals that cannot be caught like SIGKILL cannot be translated.\n\
+            Option can be repeated to translate multiple signals.\n\n\
+            Examples\n\
+            Interrupt myserver when prcs is asked to terminate:\n\
+            prcs --translate-signal=TERM:INT myserver\n\
+        "
+    )]
+    pub translate_signal: Vec<(Signal, Signal)>,
+    #[clap(
+        long,
+        require_equals = true, number_of_values = 1,
+        parse(try_from_str = parse_catchable_signal),
+        value_name = "signal",
+        about = "Ignore the signal instead of forwarding it to the process",
+        long_about = "\
+            Ignore the signal when prcs receives it instead of forwarding it to the process.\n\
+            Signal is given by name like 'HUP' or 'SIGHUP', or by number. Signals that cannot be caught like SIGKILL cannot be swallowed.\n\
+            Option can be repeated to swallow multiple signals.\n\n\
+            Examples\n\
+            Keep mycmd running when the terminal is closed:\n\
+            prcs --swallow-signal=HUP mycmd\n\
+        "
+    )]
+    pub swallow_signal: Vec<Signal>,
+    #[clap(
+        long,
+        about = "Run the process in a new process group and signal the whole group",
+        long_about = "\
+            Run the process in a new process group, so that signals reach all processes that it started as well.\n\
+            Forwarded signals and --timeout-signal are sent to the whole process group,\n\
+            and any processes left in the group are killed after the process exited.\n\
+            Because the process is not in the foreground process group of the terminal anymore, it can not read from the terminal.\n\
+            Only supported on Unix-like platforms.\n\n\
+            Examples\n\
+            Run a shell script, stopping it and any commands it started after 30 seconds:\n\
+            prcs --process-group --timeout=30s myscript.sh\n\
+        "
+    )]
+    pub process_group: bool,
+    #[clap(
+        long,
+        about = "Act as an init process, reaping any orphaned processes",
+        long_about = "\
+            Act as an init process, reaping any orphaned processes so that they do not remain as zombies, like when running as PID 1 in a container.\n\
+            On Linux, prcs also becomes a subreaper, so that orphaned descendants of the process are reaped by prcs even when it is not PID 1.\n\
+            Signals are forwarded to the process and all other options apply to it as usual.\n\
+            Only supported on Unix-like platforms.\n\n\
+            Examples\n\
+            Run myserver as the entrypoint of a container, restarting it when it crashes:\n\
+            prcs --init -r myserver\n\
+        "
+    )]
+    pub init: bool,
+    #[clap(
+        long,
+        default_value = "KILL",
+        parse(try_from_str = parse_signal_or_zero),
+        value_name = "signal",
+        about = "Signal the process receives when prcs dies",
+        long_about = "\
+            Signal the process receives when prcs dies, given by name like 'TERM' or 'SIGTERM', or by number.\n\
+            Ensures the process does not keep running unsupervised if prcs is killed. 0 disables this.\n\
+            Only the process itself receives the signal, even with --process-group.\n\
+            Only supported on Linux.\n\n\
+            Examples\n\
+            Run mycmd, letting it shut down gracefully if prcs is killed:\n\
+            prcs --parent-death-signal=TERM mycmd\n\
+        "
+    )]
+    pub parent_death_signal: Signal,
     #[clap(
         long, short,
         require_equals = true, min_values = 0,
@@ -38,7 +331,18 @@ pub struct Opts {
         long_about = "\
             After the process exited with a matching status code (by default only 0 matches), do nothing until interrupted.\n\
             Status can also be one or more ranges given as '[!][{>|<}][=]<status>...', which must all match the exit status of the process.\n\
-            Option can be repeated, in which case one value matching suffices to cause stalling.\n\n\
+            Status can also be a range like '1..=10' or '1..10' (excluding 10), or a list like '1,2,75'.\n\
+            Status can also be '&<mask>' to test that all bits of the mask are set, like '&4' for bit 2. After another constraint, '&' always combines constraints, so test bits there with '& &<mask>' like '!0 & &4'.\n\
+            Status can also be a signal name like 'INT', 'SIGINT' or 'sig:2' to match a process terminated by that signal, but not one exiting with 128 + signal number.\n\
+            Keywords match how the process ended: 'exited', 'signal' (terminated by a signal), 'coredump', 'timeout' and 'spawn-failed' (status 127).\n\
+            A process that failed to start only matches 'spawn-failed', not any status code or other keyword, whether negated or not, so neither '!0' nor 'failure' match it.\n\
+            Aliases are 'success' ('exited & 0'), 'failure' ('!success') and 'abort' (terminated by a signal other than SIGHUP, SIGINT, SIGTERM or SIGPIPE).\n\
+            Constraint sets are given as '@<name>', like '@curl-transient', '@wget-transient', '@rsync-partial', '@rsync-transient', '@ssh-transient' or '@git-transient'.\n\
+            More sets can be defined as lines of '<name> = <constraints>' in the file at $PRCS_CONSTRAINT_SETS, or else $XDG_CONFIG_HOME/prcs/constraint-sets.\n\
+            These can be combined with '&' (also implied by whitespace), '|', '!' and parentheses, like '(>=1 & <=5) | signal & SIGTERM'.\n\
+            Option can be repeated, in which case one value matching suffices to cause stalling.\n\
+            When interrupted by a signal like SIGINT or SIGTERM, or when stalling ends because of --stall-for or --stall-until-file,\n\
+            exit with the status of the process, or --stall-exit-status.\n\n\
             Examples\n\
             Do nothing until interrupted (assuming true exits with 0):\n\
             prcs -s true\n\
@@ -46,7 +350,47 @@ pub struct Opts {
             prcs -s='>42!100' -s=21 mycmd\n\
         "
     )]
-    pub stall: Option<Vec<Vec<StatusConstraint>>>,
+    pub stall: Option<Vec<ConstraintExpression>>,
+    #[clap(
+        long,
+        parse(try_from_str = humantime::parse_duration),
+        value_name = "duration",
+        about = "Stop stalling after this duration",
+        long_about = "\
+            Stop stalling after this duration.\n\
+            Has no effect without --stall.\n\n\
+            Examples\n\
+            Keep the container running for an hour after mycmd succeeded:\n\
+            prcs -s --stall-for=1h mycmd\n\
+        "
+    )]
+    pub stall_for: Option<Duration>,
+    #[clap(
+        long,
+        value_name = "path",
+        about = "Stop stalling once this file exists",
+        long_about = "\
+            Stop stalling once a file exists at this path.\n\
+            Has no effect without --stall.\n\n\
+            Examples\n\
+            Keep the container running after mycmd succeeded, until /tmp/done is created:\n\
+            prcs -s --stall-until-file=/tmp/done mycmd\n\
+        "
+    )]
+    pub stall_until_file: Option<PathBuf>,
+    #[clap(
+        long,
+        value_name = "status",
+        about = "Exit with this status after stalling instead of the status of the process",
+        long_about = "\
+            Exit with this status after stalling instead of the status of the process.\n\
+            Has no effect without --stall.\n\n\
+            Examples\n\
+            Stall after mycmd failed, exiting successfully when interrupted:\n\
+            prcs -s='!0' --stall-exit-status=0 mycmd\n\
+        "
+    )]
+    pub stall_exit_status: Option<StatusCode>,
 
     pub command: String,
     pub args: Vec<String>,
@@ -0,0 +1,211 @@
+use std::{num::ParseFloatError, time::Duration};
+
+#[derive(Debug, PartialEq)]
+pub enum Backoff {
+    Fixed,
+    Linear(Duration),
+    Exponential(f64),
+}
+#[derive(Debug, PartialEq)]
+pub struct Delay {
+    initial: Duration,
+    backoff: Backoff,
+    max: Option<Duration>,
+    jitter: Option<Duration>,
+}
+
+impl Delay {
+    /// Delay before the rerun with the given index, the first rerun having index 0.
+    pub fn before_rerun(&self, rerun: u32) -> Duration {
+        let delay = match self.backoff {
+            Backoff::Fixed => Some(self.initial),
+            Backoff::Linear(step) => step
+                .checked_mul(rerun)
+                .and_then(|increase| self.initial.checked_add(increase)),
+            Backoff::Exponential(factor) => Duration::try_from_secs_f64(
+                self.initial.as_secs_f64() * factor.powf(f64::from(rerun)),
+            )
+            .ok(),
+        }
+        .unwrap_or(Duration::MAX);
+        let delay = self.max.map_or(delay, |max| delay.min(max));
+
+        match self.jitter {
+            Some(jitter) if !jitter.is_zero() => delay.saturating_add(Duration::from_nanos(
+                fastrand::u64(0..=u64::try_from(jitter.as_nanos()).unwrap_or(u64::MAX)),
+            )),
+            _ => delay,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseDelayError<I> {
+    Duration(I, humantime::DurationError),
+    Factor(I, ParseFloatError),
+    InvalidFactor(I),
+}
+impl<I: std::fmt::Display> std::fmt::Display for ParseDelayError<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            ParseDelayError::Duration(input, err) => f.write_fmt(format_args!(
+                "Failed to parse '{}' as a duration: {}",
+                input, err
+            )),
+            ParseDelayError::Factor(input, err) => f.write_fmt(format_args!(
+                "Failed to parse '{}' as a backoff factor: {}",
+                input, err
+            )),
+            ParseDelayError::InvalidFactor(input) => f.write_fmt(format_args!(
+                "Backoff factor '{}' must be a finite number greater than 0",
+                input
+            )),
+        }
+    }
+}
+
+fn parse_duration(input: &str) -> Result<Duration, ParseDelayError<&str>> {
+    let input = input.trim();
+    humantime::parse_duration(input).map_err(|err| ParseDelayError::Duration(input, err))
+}
+fn parse_factor(input: &str) -> Result<f64, ParseDelayError<&str>> {
+    let input = input.trim();
+    match input.parse::<f64>() {
+        Ok(factor) if factor.is_finite() && factor > 0.0 => Ok(factor),
+        Ok(_) => Err(ParseDelayError::InvalidFactor(input)),
+        Err(err) => Err(ParseDelayError::Factor(input, err)),
+    }
+}
+
+/// Parses a delay given as `<initial>[{+<step>|*<factor>}][..<max>][~<jitter>]`.
+pub fn parse_delay(input: &str) -> Result<Delay, ParseDelayError<&str>> {
+    let (input, jitter) = match input.split_once('~') {
+        Some((input, jitter)) => (input, Some(parse_duration(jitter)?)),
+        None => (input, None),
+    };
+    let (input, max) = match input.split_once("..") {
+        Some((input, max)) => (input, Some(parse_duration(max)?)),
+        None => (input, None),
+    };
+    let (input, backoff) = if let Some((input, step)) = input.split_once('+') {
+        (input, Backoff::Linear(parse_duration(step)?))
+    } else if let Some((input, factor)) = input.split_once('*') {
+        (input, Backoff::Exponential(parse_factor(factor)?))
+    } else {
+        (input, Backoff::Fixed)
+    };
+
+    Ok(Delay {
+        initial: parse_duration(input)?,
+        backoff,
+        max,
+        jitter,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_delay, Backoff, Delay};
+    use std::time::Duration;
+
+    // parse
+
+    #[test]
+    fn fixed() {
+        assert_eq!(
+            parse_delay("1s"),
+            Ok(Delay {
+                initial: Duration::from_secs(1),
+                backoff: Backoff::Fixed,
+                max: None,
+                jitter: None
+            })
+        )
+    }
+    #[test]
+    fn linear() {
+        assert_eq!(
+            parse_delay("1s+500ms"),
+            Ok(Delay {
+                initial: Duration::from_secs(1),
+                backoff: Backoff::Linear(Duration::from_millis(500)),
+                max: None,
+                jitter: None
+            })
+        )
+    }
+    #[test]
+    fn exponential() {
+        assert_eq!(
+            parse_delay("1s*1.5"),
+            Ok(Delay {
+                initial: Duration::from_secs(1),
+                backoff: Backoff::Exponential(1.5),
+                max: None,
+                jitter: None
+            })
+        )
+    }
+    #[test]
+    fn max_and_jitter() {
+        assert_eq!(
+            parse_delay(" 100ms * 2 .. 1m ~ 50ms "),
+            Ok(Delay {
+                initial: Duration::from_millis(100),
+                backoff: Backoff::Exponential(2.0),
+                max: Some(Duration::from_secs(60)),
+                jitter: Some(Duration::from_millis(50))
+            })
+        )
+    }
+
+    #[test]
+    fn invalid_duration() {
+        insta::assert_snapshot!(parse_delay("1x").unwrap_err().to_string(), @"Failed to parse '1x' as a duration: unknown time unit \"x\", supported units: ns, us/µs, ms, sec, min, hours, days, weeks, months, years (and few variations)");
+    }
+    #[test]
+    fn invalid_factor() {
+        insta::assert_snapshot!(parse_delay("1s*x").unwrap_err().to_string(), @"Failed to parse 'x' as a backoff factor: invalid float literal");
+    }
+    #[test]
+    fn zero_factor() {
+        insta::assert_snapshot!(parse_delay("1s*0").unwrap_err().to_string(), @"Backoff factor '0' must be a finite number greater than 0");
+    }
+
+    // before_rerun
+
+    #[test]
+    fn fixed_delay() {
+        let delay = parse_delay("1s").unwrap();
+        assert_eq!(delay.before_rerun(0), Duration::from_secs(1));
+        assert_eq!(delay.before_rerun(5), Duration::from_secs(1));
+    }
+    #[test]
+    fn linear_delay() {
+        let delay = parse_delay("1s+500ms").unwrap();
+        assert_eq!(delay.before_rerun(0), Duration::from_secs(1));
+        assert_eq!(delay.before_rerun(2), Duration::from_secs(2));
+    }
+    #[test]
+    fn exponential_delay() {
+        let delay = parse_delay("1s*2").unwrap();
+        assert_eq!(delay.before_rerun(0), Duration::from_secs(1));
+        assert_eq!(delay.before_rerun(3), Duration::from_secs(8));
+    }
+    #[test]
+    fn capped_delay() {
+        let delay = parse_delay("1s*2..5s").unwrap();
+        assert_eq!(delay.before_rerun(2), Duration::from_secs(4));
+        assert_eq!(delay.before_rerun(3), Duration::from_secs(5));
+        assert_eq!(delay.before_rerun(u32::MAX), Duration::from_secs(5));
+    }
+    #[test]
+    fn jittered_delay() {
+        let delay = parse_delay("1s~100ms").unwrap();
+        for _ in 0..100 {
+            let before_rerun = delay.before_rerun(0);
+            assert!(before_rerun >= Duration::from_secs(1));
+            assert!(before_rerun <= Duration::from_millis(1100));
+        }
+    }
+}
@@ -1,31 +1,84 @@
 extern crate clap;
+extern crate fastrand;
+extern crate humantime;
 extern crate nom;
 
 mod cli;
+mod delay;
 mod options;
+mod rate_limit;
+mod rerun_policy;
+mod signal;
 mod status_code;
 mod status_constraints;
 
-use options::{rerun, stall};
-use status_code::get_status_code;
+use options::{init, rerun, stall, timeout};
+use status_code::Outcome;
 use status_constraints::StatusCode;
 use std::process;
 
 pub use cli::Opts;
 
+fn command(opts: &Opts, attempt: &rerun::Attempt) -> process::Command {
+    let mut command = process::Command::new(&opts.command);
+    command
+        .args(&opts.args)
+        .env("PRCS_ATTEMPT", attempt.number.to_string())
+        .env("PRCS_ELAPSED_MS", attempt.elapsed.as_millis().to_string());
+    match attempt.previous_status {
+        Some(previous_status) => command.env("PRCS_PREVIOUS_STATUS", previous_status.to_string()),
+        None => command.env_remove("PRCS_PREVIOUS_STATUS"),
+    };
+    match attempt.max_tries {
+        Some(max_tries) => command.env("PRCS_MAX_TRIES", max_tries.to_string()),
+        None => command.env_remove("PRCS_MAX_TRIES"),
+    };
+
+    if opts.idle_timeout.is_some() {
+        command
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::piped());
+    }
+
+    #[cfg(target_family = "unix")]
+    if opts.process_group {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    signal::on_parent_death(&mut command, opts.parent_death_signal);
+
+    command
+}
+
 pub fn run(opts: &Opts) -> StatusCode {
-    stall::handle(&opts, || {
-        rerun::handle(&opts, || {
-            get_status_code(
-                &process::Command::new(&opts.command)
-                    .args(&opts.args)
-                    .status()
-                    .unwrap_or_else(|err| {
-                        eprintln!("Failed to run '{}'", opts.command);
-                        eprintln!("{}", err);
-                        process::exit(1)
-                    }),
-            )
+    signal::forward_signals(opts).unwrap_or_else(|err| {
+        eprintln!("Failed to set up forwarding signals");
+        eprintln!("{}", err);
+    });
+
+    init::handle(opts, || {
+        stall::handle(opts, || {
+            rerun::handle(opts, |attempt| {
+                let (mut child, _forwarding) =
+                    match signal::spawn(&mut command(opts, attempt), opts.process_group) {
+                        Ok(spawned) => spawned,
+                        Err(err) => {
+                            eprintln!("Failed to run '{}'", opts.command);
+                            eprintln!("{}", err);
+                            return Outcome::SpawnFailed;
+                        }
+                    };
+                let outcome = timeout::wait(opts, &mut child);
+                if opts.process_group {
+                    // descendants that are still running would otherwise leak
+                    signal::kill(&mut child, true).ok();
+                }
+                outcome.unwrap_or_else(|err| {
+                    eprintln!("Failed to wait for '{}'", opts.command);
+                    eprintln!("{}", err);
+                    process::exit(1)
+                })
+            })
         })
     })
 }
@@ -0,0 +1,52 @@
+use crate::{cli::Opts, status_constraints::StatusCode};
+
+#[cfg(target_family = "unix")]
+const REAP_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Reaps all terminated processes except for the running child process, which is waited for separately.
+#[cfg(target_family = "unix")]
+fn reap_orphans() {
+    crate::signal::with_child(|child| loop {
+        // look at the next terminated process without reaping it, in case it is the child process
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        let result = unsafe {
+            libc::waitid(
+                libc::P_ALL,
+                0,
+                &mut info,
+                libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+            )
+        };
+        let pid = unsafe { info.si_pid() };
+        if result != 0 || pid == 0 || pid == child {
+            return;
+        }
+        unsafe { libc::waitpid(pid, std::ptr::null_mut(), libc::WNOHANG) };
+    })
+}
+
+#[cfg(target_family = "unix")]
+fn become_init() {
+    #[cfg(target_os = "linux")]
+    if unsafe { libc::prctl(libc::PR_SET_CHILD_SUBREAPER, 1) } != 0 {
+        eprintln!("Failed to become a subreaper");
+        eprintln!("{}", std::io::Error::last_os_error());
+    }
+
+    std::thread::spawn(|| loop {
+        reap_orphans();
+        std::thread::sleep(REAP_INTERVAL);
+    });
+}
+#[cfg(not(target_family = "unix"))]
+fn become_init() {}
+
+pub fn handle<F>(opts: &Opts, delegate: F) -> StatusCode
+where
+    F: Fn() -> StatusCode,
+{
+    if opts.init {
+        become_init();
+    }
+    delegate()
+}
@@ -1,2 +1,4 @@
+pub mod init;
 pub mod rerun;
 pub mod stall;
+pub mod timeout;
@@ -1,14 +1,189 @@
-use crate::status_constraints::MatchStatusCode;
+use crate::rate_limit::RateLimiter;
+use crate::signal;
+use crate::status_code::Outcome;
+use crate::status_constraints::MatchOutcome;
 use crate::{cli::Opts, status_constraints::StatusCode};
+use clap::ArgEnum;
+use std::num::NonZeroU32;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
 
-pub fn handle<F>(opts: &Opts, delegate: F) -> StatusCode
+/// Status code to exit with when giving up because of `--rerun-limit`.
+pub const RERUN_LIMIT_STATUS_CODE: StatusCode = 125;
+
+/// Which status to exit with after rerunning the process.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum FinalStatus {
+    Last,
+    First,
+    Worst,
+    Best,
+    AnySuccess,
+}
+
+impl FinalStatus {
+    pub fn select(self, selected: Option<Outcome>, latest: Outcome) -> Outcome {
+        // 0 is the best status code, any other status code is worse the higher it is
+        let rank = |outcome: Outcome| (outcome.status_code() != 0, outcome.status_code());
+        match (self, selected) {
+            (_, None) | (FinalStatus::Last, _) => latest,
+            (FinalStatus::First, Some(selected)) => selected,
+            (FinalStatus::Worst, Some(selected)) => {
+                std::cmp::max_by_key(selected, latest, |&outcome| rank(outcome))
+            }
+            (FinalStatus::Best, Some(selected)) => {
+                std::cmp::min_by_key(selected, latest, |&outcome| rank(outcome))
+            }
+            (FinalStatus::AnySuccess, Some(Outcome::Exited(0))) => Outcome::Exited(0),
+            (FinalStatus::AnySuccess, Some(_)) => latest,
+        }
+    }
+}
+
+/// Information about a run of the process, exposed to it via environment variables.
+pub struct Attempt {
+    /// Number of this run, starting at 1 and starting over after a stable run.
+    pub number: u32,
+    pub previous_status: Option<StatusCode>,
+    pub max_tries: Option<NonZeroU32>,
+    /// Time since the process was first started.
+    pub elapsed: Duration,
+}
+
+pub fn handle<F>(opts: &Opts, delegate: F) -> Outcome
 where
-    F: Fn() -> StatusCode,
+    F: Fn(&Attempt) -> Outcome,
 {
+    let start = Instant::now();
+    let within_rerun_for = |wait: Duration| {
+        opts.rerun_for
+            .is_none_or(|rerun_for| start.elapsed().saturating_add(wait) < rerun_for)
+    };
+    let mut rate_limiter = opts.rerun_limit.as_ref().map(RateLimiter::new);
+    let groups: Vec<_> = opts.rerun.iter().flatten().chain(&opts.restart).collect();
+    let mut tries = 0;
+    let mut group_tries = vec![0; groups.len()];
+    let mut previous_status = None;
+    let mut final_status = None;
     loop {
-        let child_status = delegate();
-        if !opts.rerun.iter().any(|rerun| rerun.matches(child_status)) {
-            return child_status;
+        let attempt_start = Instant::now();
+        let outcome = delegate(&Attempt {
+            number: tries + 1,
+            previous_status,
+            max_tries: opts.max_tries,
+            elapsed: start.elapsed(),
+        });
+        previous_status = Some(outcome.status_code());
+        let selected_status = opts.exit_status.select(final_status, outcome);
+        final_status = Some(selected_status);
+        if opts
+            .rerun_stable_after
+            .is_some_and(|stable_after| attempt_start.elapsed() >= stable_after)
+        {
+            tries = 0;
+            group_tries.fill(0);
+        }
+        tries += 1;
+
+        let (group_index, group) = match groups
+            .iter()
+            .enumerate()
+            .find(|(_, group)| group.matches(outcome))
+        {
+            Some(_) if signal::terminating() => return selected_status,
+            Some(matching_group) => matching_group,
+            None => return selected_status,
+        };
+        group_tries[group_index] += 1;
+        if opts
+            .max_tries
+            .is_some_and(|max_tries| tries >= max_tries.get())
+            || group
+                .policy
+                .tries
+                .is_some_and(|max_tries| group_tries[group_index] >= max_tries.get())
+        {
+            return selected_status;
+        }
+
+        let delay = match (&group.policy.delay, &opts.rerun_delay) {
+            (Some(delay), _) => delay.before_rerun(group_tries[group_index] - 1),
+            (None, Some(delay)) => delay.before_rerun(tries - 1),
+            (None, None) => Duration::ZERO,
+        };
+        if !within_rerun_for(delay) {
+            return selected_status;
+        }
+        sleep(delay);
+
+        if let Some(rate_limiter) = &mut rate_limiter {
+            if rate_limiter.exceeded() {
+                match opts.rerun_limit_cooldown {
+                    Some(cooldown) if within_rerun_for(cooldown) => {
+                        sleep(cooldown);
+                        rate_limiter.reset();
+                    }
+                    Some(_) => return selected_status,
+                    None => {
+                        eprintln!(
+                            "Rerun limit of {} exceeded, giving up",
+                            opts.rerun_limit.as_ref().unwrap()
+                        );
+                        // there is no process to match the status of, so report it like an exit status
+                        return Outcome::Exited(RERUN_LIMIT_STATUS_CODE);
+                    }
+                }
+            }
+            rate_limiter.record();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FinalStatus;
+    use crate::status_code::Outcome;
+
+    fn select(final_status: FinalStatus, codes: &[i32]) -> i32 {
+        codes
+            .iter()
+            .fold(None, |selected, &code| {
+                Some(final_status.select(selected, Outcome::Exited(code)))
+            })
+            .unwrap()
+            .status_code()
+    }
+
+    #[test]
+    fn last() {
+        assert_eq!(select(FinalStatus::Last, &[1, 0, 2]), 2)
+    }
+    #[test]
+    fn first() {
+        assert_eq!(select(FinalStatus::First, &[1, 0, 2]), 1)
+    }
+    #[test]
+    fn worst() {
+        assert_eq!(select(FinalStatus::Worst, &[1, 3, 0, 2]), 3)
+    }
+    #[test]
+    fn worst_prefers_failure() {
+        assert_eq!(select(FinalStatus::Worst, &[0, -1, 0]), -1)
+    }
+    #[test]
+    fn best() {
+        assert_eq!(select(FinalStatus::Best, &[3, 1, 2]), 1)
+    }
+    #[test]
+    fn best_prefers_success() {
+        assert_eq!(select(FinalStatus::Best, &[-1, 0, 1]), 0)
+    }
+    #[test]
+    fn any_success() {
+        assert_eq!(select(FinalStatus::AnySuccess, &[1, 0, 2]), 0)
+    }
+    #[test]
+    fn any_success_without_success() {
+        assert_eq!(select(FinalStatus::AnySuccess, &[1, 3, 2]), 2)
+    }
+}
@@ -1,15 +1,45 @@
-use crate::status_constraints::MatchStatusCode;
+use crate::signal;
+use crate::status_code::Outcome;
+use crate::status_constraints::MatchOutcome;
 use crate::{cli::Opts, status_constraints::StatusCode};
+use std::thread::park_timeout;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+fn stall(opts: &Opts) {
+    // a deadline too far in the future to represent is never reached
+    let deadline = opts
+        .stall_for
+        .and_then(|stall_for| Instant::now().checked_add(stall_for));
+    signal::interruptible(|| loop {
+        if signal::terminating()
+            || opts
+                .stall_until_file
+                .as_ref()
+                .is_some_and(|file| file.exists())
+        {
+            return;
+        }
+        let timeout = match deadline {
+            Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                Some(remaining) if !remaining.is_zero() => remaining.min(POLL_INTERVAL),
+                _ => return,
+            },
+            None => POLL_INTERVAL,
+        };
+        park_timeout(timeout)
+    })
+}
 
 pub fn handle<F>(opts: &Opts, delegate: F) -> StatusCode
 where
-    F: Fn() -> StatusCode,
+    F: Fn() -> Outcome,
 {
-    let child_status = delegate();
-    if opts.stall.iter().any(|stall| stall.matches(child_status)) {
-        loop {
-            std::thread::park()
-        }
+    let outcome = delegate();
+    if opts.stall.iter().any(|stall| stall.matches(outcome)) {
+        stall(opts);
+        return opts.stall_exit_status.unwrap_or(outcome.status_code());
     }
-    child_status
+    outcome.status_code()
 }
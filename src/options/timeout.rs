@@ -0,0 +1,132 @@
+use crate::signal::{kill, send_signal};
+use crate::status_code::{get_outcome, Outcome};
+use crate::{cli::Opts, status_constraints::StatusCode};
+use std::io::{self, Read, Write};
+use std::process::Child;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Status code to use when the process timed out, like `timeout` from GNU coreutils.
+pub const TIMEOUT_STATUS_CODE: StatusCode = 124;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// How long to keep waiting for output after the process exited, in case descendants still hold it open.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Output of the process, passed through by prcs to keep track of when it last printed something.
+struct Output {
+    last_activity: Arc<Mutex<Instant>>,
+    drained: mpsc::Receiver<()>,
+    streams: usize,
+}
+
+impl Output {
+    fn pass_through(child: &mut Child) -> Self {
+        let last_activity = Arc::new(Mutex::new(Instant::now()));
+        let (drained_sender, drained) = mpsc::channel();
+        let mut streams = 0;
+
+        let mut pump = |mut input: Box<dyn Read + Send>, mut output: Box<dyn Write + Send>| {
+            let last_activity = last_activity.clone();
+            let drained_sender = drained_sender.clone();
+            std::thread::spawn(move || {
+                let mut buf = [0; 8192];
+                while let Ok(len @ 1..) = input.read(&mut buf) {
+                    *last_activity.lock().unwrap() = Instant::now();
+                    // keep reading even if prcs cannot write, so the process does not block
+                    output
+                        .write_all(&buf[..len])
+                        .and_then(|()| output.flush())
+                        .ok();
+                }
+                drained_sender.send(()).ok();
+            });
+            streams += 1;
+        };
+        if let Some(stdout) = child.stdout.take() {
+            pump(Box::new(stdout), Box::new(io::stdout()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            pump(Box::new(stderr), Box::new(io::stderr()));
+        }
+
+        Output {
+            last_activity,
+            drained,
+            streams,
+        }
+    }
+
+    fn last_activity(&self) -> Instant {
+        *self.last_activity.lock().unwrap()
+    }
+
+    fn drain(self) {
+        let deadline = Instant::now() + DRAIN_TIMEOUT;
+        for _ in 0..self.streams {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if self.drained.recv_timeout(remaining).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn wait_until(
+    child: &mut Child,
+    deadline: impl Fn() -> Option<Instant>,
+) -> io::Result<Option<Outcome>> {
+    loop {
+        if let Some(child_status) = child.try_wait()? {
+            return Ok(Some(get_outcome(&child_status)));
+        }
+        let now = Instant::now();
+        let poll_interval = match deadline() {
+            Some(deadline) if now >= deadline => return Ok(None),
+            Some(deadline) => POLL_INTERVAL.min(deadline - now),
+            None => POLL_INTERVAL,
+        };
+        sleep(poll_interval);
+    }
+}
+
+fn wait_with_timeouts(
+    opts: &Opts,
+    child: &mut Child,
+    output: Option<&Output>,
+) -> io::Result<Outcome> {
+    let start = Instant::now();
+    let deadline = || {
+        // deadlines too far in the future to represent are never reached
+        let timeout = opts.timeout.and_then(|timeout| start.checked_add(timeout));
+        let idle_timeout = output
+            .zip(opts.idle_timeout)
+            .and_then(|(output, idle_timeout)| output.last_activity().checked_add(idle_timeout));
+        timeout.into_iter().chain(idle_timeout).min()
+    };
+    if let Some(outcome) = wait_until(child, deadline)? {
+        return Ok(outcome);
+    }
+
+    send_signal(child, opts.timeout_signal, opts.process_group)?;
+    let kill_deadline = Instant::now().checked_add(opts.kill_after);
+    if wait_until(child, || kill_deadline)?.is_none() {
+        kill(child, opts.process_group)?;
+        child.wait()?;
+    }
+    Ok(Outcome::TimedOut)
+}
+
+pub fn wait(opts: &Opts, child: &mut Child) -> io::Result<Outcome> {
+    if opts.timeout.is_none() && opts.idle_timeout.is_none() {
+        return child.wait().map(|child_status| get_outcome(&child_status));
+    }
+
+    let output = opts.idle_timeout.map(|_| Output::pass_through(child));
+    let outcome = wait_with_timeouts(opts, child, output.as_ref());
+    if let Some(output) = output {
+        output.drain();
+    }
+    outcome
+}
@@ -0,0 +1,150 @@
+use std::{collections::VecDeque, num::ParseIntError, time::Duration, time::Instant};
+
+#[derive(Debug, PartialEq)]
+pub struct RateLimit {
+    burst: u32,
+    interval: Duration,
+}
+
+impl std::fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.write_fmt(format_args!(
+            "{} within {}",
+            self.burst,
+            humantime::format_duration(self.interval)
+        ))
+    }
+}
+
+/// Keeps track of recent events to check them against a [`RateLimit`].
+pub struct RateLimiter<'a> {
+    limit: &'a RateLimit,
+    events: VecDeque<Instant>,
+}
+
+impl<'a> RateLimiter<'a> {
+    pub fn new(limit: &'a RateLimit) -> Self {
+        RateLimiter {
+            limit,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Whether another event right now would exceed the limit.
+    pub fn exceeded(&mut self) -> bool {
+        let now = Instant::now();
+        while let Some(event) = self.events.front() {
+            if now.duration_since(*event) < self.limit.interval {
+                break;
+            }
+            self.events.pop_front();
+        }
+        self.events.len() >= self.limit.burst as usize
+    }
+
+    pub fn record(&mut self) {
+        self.events.push_back(Instant::now());
+    }
+
+    pub fn reset(&mut self) {
+        self.events.clear();
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseRateLimitError<I> {
+    Burst(I, ParseIntError),
+    Interval(I, humantime::DurationError),
+    MissingInterval(I),
+}
+impl<I: std::fmt::Display> std::fmt::Display for ParseRateLimitError<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            ParseRateLimitError::Burst(input, err) => f.write_fmt(format_args!(
+                "Failed to parse '{}' as a number of reruns: {}",
+                input, err
+            )),
+            ParseRateLimitError::Interval(input, err) => f.write_fmt(format_args!(
+                "Failed to parse '{}' as a duration: {}",
+                input, err
+            )),
+            ParseRateLimitError::MissingInterval(input) => f.write_fmt(format_args!(
+                "Expected '<reruns>/<duration>', got: {}",
+                input
+            )),
+        }
+    }
+}
+
+/// Parses a rate limit given as `<burst>/<interval>`.
+pub fn parse_rate_limit(input: &str) -> Result<RateLimit, ParseRateLimitError<&str>> {
+    let (burst, interval) = input
+        .split_once('/')
+        .ok_or(ParseRateLimitError::MissingInterval(input))?;
+    let (burst, interval) = (burst.trim(), interval.trim());
+
+    Ok(RateLimit {
+        burst: burst
+            .parse()
+            .map_err(|err| ParseRateLimitError::Burst(burst, err))?,
+        interval: humantime::parse_duration(interval)
+            .map_err(|err| ParseRateLimitError::Interval(interval, err))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_rate_limit, RateLimit, RateLimiter};
+    use std::time::Duration;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            parse_rate_limit("5/10s"),
+            Ok(RateLimit {
+                burst: 5,
+                interval: Duration::from_secs(10)
+            })
+        )
+    }
+    #[test]
+    fn parse_whitespace() {
+        assert_eq!(
+            parse_rate_limit(" 5 / 1m 30s "),
+            Ok(RateLimit {
+                burst: 5,
+                interval: Duration::from_secs(90)
+            })
+        )
+    }
+
+    #[test]
+    fn missing_interval() {
+        insta::assert_snapshot!(parse_rate_limit("5").unwrap_err().to_string(), @"Expected '<reruns>/<duration>', got: 5");
+    }
+    #[test]
+    fn invalid_burst() {
+        insta::assert_snapshot!(parse_rate_limit("x/10s").unwrap_err().to_string(), @"Failed to parse 'x' as a number of reruns: invalid digit found in string");
+    }
+
+    #[test]
+    fn exceeded_after_burst() {
+        let limit = parse_rate_limit("2/1h").unwrap();
+        let mut limiter = RateLimiter::new(&limit);
+        assert!(!limiter.exceeded());
+        limiter.record();
+        assert!(!limiter.exceeded());
+        limiter.record();
+        assert!(limiter.exceeded());
+        limiter.reset();
+        assert!(!limiter.exceeded());
+    }
+    #[test]
+    fn not_exceeded_after_interval() {
+        let limit = parse_rate_limit("1/1ms").unwrap();
+        let mut limiter = RateLimiter::new(&limit);
+        limiter.record();
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(!limiter.exceeded());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::delay::{parse_delay, Delay, ParseDelayError};
+use crate::status_code::Outcome;
+use crate::status_constraints::{
+    parse_status_constraints, ConstraintExpression, MatchOutcome, ParseStatusConstraintsError,
+};
+use std::num::{NonZeroU32, ParseIntError};
+
+#[derive(Debug, Default, PartialEq)]
+pub struct RerunPolicy {
+    pub tries: Option<NonZeroU32>,
+    pub delay: Option<Delay>,
+}
+#[derive(Debug, PartialEq)]
+pub struct RerunGroup {
+    pub constraints: ConstraintExpression,
+    pub policy: RerunPolicy,
+}
+
+impl MatchOutcome for RerunGroup {
+    fn matches(&self, outcome: Outcome) -> bool {
+        self.constraints.matches(outcome)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseRerunGroupError<I> {
+    Constraints(ParseStatusConstraintsError<I>),
+    Tries(I, ParseIntError),
+    Delay(ParseDelayError<I>),
+    UnknownSetting(I),
+    UnknownRestartPolicy(I),
+}
+impl std::fmt::Display for ParseRerunGroupError<&str> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            ParseRerunGroupError::Constraints(err) => err.fmt(f),
+            ParseRerunGroupError::Tries(input, err) => f.write_fmt(format_args!(
+                "Failed to parse '{}' as a number of tries: {}",
+                input, err
+            )),
+            ParseRerunGroupError::Delay(err) => err.fmt(f),
+            ParseRerunGroupError::UnknownSetting(input) => f.write_fmt(format_args!(
+                "Expected one of 'tries=<tries>', 'delay=<delay>', got: {}",
+                input
+            )),
+            ParseRerunGroupError::UnknownRestartPolicy(input) => f.write_fmt(format_args!(
+                "Expected one of {}, got: {}",
+                RESTART_POLICIES
+                    .iter()
+                    .map(|(name, _)| format!("'{}'", name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                input
+            )),
+        }
+    }
+}
+
+/// Parses status constraints optionally followed by settings, given as `<status>[; tries=<tries>][; delay=<delay>]`.
+pub fn parse_rerun_group(input: &str) -> Result<RerunGroup, ParseRerunGroupError<&str>> {
+    let mut parts = input.split(';');
+    let constraints = parse_status_constraints(parts.next().unwrap_or_default())
+        .map_err(ParseRerunGroupError::Constraints)?;
+
+    let mut policy = RerunPolicy::default();
+    for setting in parts.map(str::trim).filter(|setting| !setting.is_empty()) {
+        match setting.split_once('=') {
+            Some((key, tries)) if key.trim() == "tries" => {
+                let tries = tries.trim();
+                policy.tries = Some(
+                    tries
+                        .parse()
+                        .map_err(|err| ParseRerunGroupError::Tries(tries, err))?,
+                );
+            }
+            Some((key, delay)) if key.trim() == "delay" => {
+                policy.delay = Some(parse_delay(delay).map_err(ParseRerunGroupError::Delay)?);
+            }
+            _ => return Err(ParseRerunGroupError::UnknownSetting(setting)),
+        }
+    }
+
+    Ok(RerunGroup {
+        constraints,
+        policy,
+    })
+}
+
+/// Presets for `--restart` and the status constraints they expand to, like the `Restart=` setting of systemd services.
+pub const RESTART_POLICIES: &[(&str, &str)] = &[
+    ("always", ""),
+    ("on-success", "success | signal & (HUP | INT | TERM | PIPE)"),
+    ("on-failure", "exited !0 | abort | timeout | spawn-failed"),
+    ("on-abnormal", "abort | timeout"),
+    ("on-abort", "abort"),
+];
+
+/// Parses the name of a preset from [`RESTART_POLICIES`].
+pub fn parse_restart_policy(input: &str) -> Result<RerunGroup, ParseRerunGroupError<&str>> {
+    RESTART_POLICIES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(input.trim()))
+        .ok_or(ParseRerunGroupError::UnknownRestartPolicy(input))
+        .and_then(|(_, constraints)| parse_rerun_group(constraints))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_rerun_group, parse_restart_policy, RerunPolicy, RESTART_POLICIES};
+    use crate::delay::parse_delay;
+    use crate::status_code::Outcome;
+    use crate::status_constraints::parse_status_constraints;
+    use crate::status_constraints::MatchOutcome;
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn constraints_only() {
+        let group = parse_rerun_group("!0").unwrap();
+        assert_eq!(group.constraints, parse_status_constraints("!0").unwrap());
+        assert_eq!(group.policy, RerunPolicy::default());
+    }
+    #[test]
+    fn tries_and_delay() {
+        let group = parse_rerun_group("75; tries=10; delay=30s*2").unwrap();
+        assert_eq!(group.constraints, parse_status_constraints("75").unwrap());
+        assert_eq!(
+            group.policy,
+            RerunPolicy {
+                tries: NonZeroU32::new(10),
+                delay: Some(parse_delay("30s*2").unwrap())
+            }
+        );
+    }
+    #[test]
+    fn whitespace() {
+        let group = parse_rerun_group(" >128 ; tries = 2 ; ").unwrap();
+        assert_eq!(group.constraints, parse_status_constraints(">128").unwrap());
+        assert_eq!(group.policy.tries, NonZeroU32::new(2));
+    }
+
+    #[test]
+    fn invalid_constraints() {
+        insta::assert_snapshot!(parse_rerun_group("#; tries=2").unwrap_err().to_string(), @r###"
+            Expected one of '!><&=(@-0123456789' or a name, got: #
+              #
+              ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn invalid_tries() {
+        insta::assert_snapshot!(parse_rerun_group("75; tries=0").unwrap_err().to_string(), @"Failed to parse '0' as a number of tries: number would be zero for non-zero type");
+    }
+    #[test]
+    fn unknown_setting() {
+        insta::assert_snapshot!(parse_rerun_group("75; foo=bar").unwrap_err().to_string(), @"Expected one of 'tries=<tries>', 'delay=<delay>', got: foo=bar");
+    }
+
+    #[test]
+    fn restart_policies() {
+        for (name, _) in RESTART_POLICIES {
+            assert!(parse_restart_policy(name).is_ok());
+        }
+    }
+    #[test]
+    fn restart_on_failure() {
+        let group = parse_restart_policy("on-failure").unwrap();
+        assert!(!group.matches(Outcome::Exited(0)));
+        assert!(group.matches(Outcome::Exited(1)));
+        assert!(group.matches(Outcome::TimedOut));
+        assert!(group.matches(Outcome::Signaled {
+            signal: 6,
+            core_dumped: true
+        }));
+        assert!(!group.matches(Outcome::Signaled {
+            signal: 15,
+            core_dumped: false
+        }));
+    }
+    #[test]
+    fn restart_on_abnormal() {
+        let group = parse_restart_policy("on-abnormal").unwrap();
+        assert!(!group.matches(Outcome::Exited(1)));
+        assert!(group.matches(Outcome::TimedOut));
+    }
+    #[test]
+    fn unknown_restart_policy() {
+        insta::assert_snapshot!(parse_restart_policy("never").unwrap_err().to_string(), @"Expected one of 'always', 'on-success', 'on-failure', 'on-abnormal', 'on-abort', got: never");
+    }
+}
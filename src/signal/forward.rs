@@ -0,0 +1,150 @@
+use crate::cli::Opts;
+use std::io;
+use std::process::{Child, Command};
+#[cfg(target_family = "unix")]
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+#[cfg(target_family = "unix")]
+const FORWARDED_SIGNALS: &[super::Signal] = &[
+    libc::SIGTERM,
+    libc::SIGINT,
+    libc::SIGHUP,
+    libc::SIGQUIT,
+    libc::SIGUSR1,
+    libc::SIGUSR2,
+    libc::SIGWINCH,
+];
+#[cfg(target_family = "unix")]
+const TERMINATION_SIGNALS: &[super::Signal] =
+    &[libc::SIGTERM, libc::SIGINT, libc::SIGHUP, libc::SIGQUIT];
+
+/// Process ID of the running child process, negated for its process group, or 0 if there is none.
+#[cfg(target_family = "unix")]
+static CHILD: Mutex<libc::pid_t> = Mutex::new(0);
+#[cfg(target_family = "unix")]
+static TERMINATING: AtomicBool = AtomicBool::new(false);
+/// Thread that is notified about termination signals instead of them terminating prcs,
+/// while no child process is running.
+#[cfg(target_family = "unix")]
+static INTERRUPTIBLE: Mutex<Option<std::thread::Thread>> = Mutex::new(None);
+
+#[cfg(target_family = "unix")]
+fn forward(signal: super::Signal, translations: &[(super::Signal, super::Signal)]) {
+    // keep the child from finishing being waited for before it is known whether to rerun it
+    let child = CHILD.lock().unwrap();
+    // whether the signal asks to terminate depends on what the child process receives
+    let signal = match *child {
+        0 => signal,
+        _ => translations
+            .iter()
+            .find(|(from, _)| *from == signal)
+            .map_or(signal, |(_, to)| *to),
+    };
+    let terminating = TERMINATION_SIGNALS.contains(&signal);
+    if terminating {
+        TERMINATING.store(true, Ordering::SeqCst);
+    }
+
+    match *child {
+        // nobody to forward to, so behave as if prcs did not handle the signal
+        0 if terminating => {
+            if let Some(thread) = &*INTERRUPTIBLE.lock().unwrap() {
+                thread.unpark();
+                return;
+            }
+            signal_hook::low_level::emulate_default_handler(signal).ok();
+            // still alive if the signal has no effect by default, like when running as PID 1
+            std::process::exit(128 + signal);
+        }
+        0 => {}
+        pid => unsafe {
+            libc::kill(pid, signal);
+        },
+    }
+}
+
+/// Starts forwarding signals that prcs receives to the child process passed to [`forward_to`],
+/// translating and swallowing them as specified in the options.
+/// While no child process is running, termination signals take their default effect on prcs,
+/// unless it is [`interruptible`].
+#[cfg(target_family = "unix")]
+pub fn forward_signals(opts: &Opts) -> io::Result<()> {
+    for &signal in &opts.swallow_signal {
+        // ignoring is inherited by the child process, so only do it in the signal handler
+        unsafe { signal_hook::low_level::register(signal, || {}) }?;
+    }
+
+    let translations = opts.translate_signal.clone();
+    let mut signals = signal_hook::iterator::Signals::new(
+        FORWARDED_SIGNALS
+            .iter()
+            .chain(translations.iter().map(|(from, _)| from))
+            .filter(|signal| !opts.swallow_signal.contains(signal)),
+    )?;
+    std::thread::spawn(move || {
+        for signal in signals.forever() {
+            forward(signal, &translations)
+        }
+    });
+    Ok(())
+}
+#[cfg(not(target_family = "unix"))]
+pub fn forward_signals(_opts: &Opts) -> io::Result<()> {
+    Ok(())
+}
+
+/// Forwards signals to the child process until dropped.
+pub struct Forwarding(());
+#[cfg(target_family = "unix")]
+impl Drop for Forwarding {
+    fn drop(&mut self) {
+        *CHILD.lock().unwrap() = 0;
+    }
+}
+
+/// Spawns the child process and forwards signals to it, or to its whole process group.
+#[cfg(target_family = "unix")]
+pub fn spawn(command: &mut Command, process_group: bool) -> io::Result<(Child, Forwarding)> {
+    let mut target = CHILD.lock().unwrap();
+    let child = command.spawn()?;
+    *target = super::signal_target(&child, process_group);
+    Ok((child, Forwarding(())))
+}
+#[cfg(not(target_family = "unix"))]
+pub fn spawn(command: &mut Command, _process_group: bool) -> io::Result<(Child, Forwarding)> {
+    Ok((command.spawn()?, Forwarding(())))
+}
+
+/// Calls the function with the process ID of the running child process, or 0 if there is none.
+/// No child process is spawned or finishes being waited for until the function returns.
+#[cfg(target_family = "unix")]
+pub fn with_child<T>(f: impl FnOnce(libc::pid_t) -> T) -> T {
+    f(CHILD.lock().unwrap().abs())
+}
+
+/// Calls the function, letting termination signals unpark the current thread instead of terminating prcs
+/// while no child process is running. The function can check [`terminating`] to find out about them.
+#[cfg(target_family = "unix")]
+pub fn interruptible<T>(f: impl FnOnce() -> T) -> T {
+    *INTERRUPTIBLE.lock().unwrap() = Some(std::thread::current());
+    let result = f();
+    *INTERRUPTIBLE.lock().unwrap() = None;
+    result
+}
+#[cfg(not(target_family = "unix"))]
+pub fn interruptible<T>(f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+/// Whether prcs received a signal asking it to terminate.
+#[cfg(target_family = "unix")]
+pub fn terminating() -> bool {
+    TERMINATING.load(Ordering::SeqCst)
+}
+#[cfg(not(target_family = "unix"))]
+pub fn terminating() -> bool {
+    false
+}
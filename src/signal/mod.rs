@@ -0,0 +1,325 @@
+mod forward;
+
+use std::{
+    io,
+    num::ParseIntError,
+    process::{Child, Command},
+};
+
+#[cfg(target_family = "unix")]
+pub use forward::with_child;
+pub use forward::{forward_signals, interruptible, spawn, terminating};
+
+pub type Signal = i32;
+
+#[cfg(target_family = "unix")]
+const SIGNALS: &[(&str, Signal)] = &[
+    ("HUP", libc::SIGHUP),
+    ("INT", libc::SIGINT),
+    ("QUIT", libc::SIGQUIT),
+    ("ILL", libc::SIGILL),
+    ("TRAP", libc::SIGTRAP),
+    ("ABRT", libc::SIGABRT),
+    ("BUS", libc::SIGBUS),
+    ("FPE", libc::SIGFPE),
+    ("KILL", libc::SIGKILL),
+    ("USR1", libc::SIGUSR1),
+    ("SEGV", libc::SIGSEGV),
+    ("USR2", libc::SIGUSR2),
+    ("PIPE", libc::SIGPIPE),
+    ("ALRM", libc::SIGALRM),
+    ("TERM", libc::SIGTERM),
+    ("CHLD", libc::SIGCHLD),
+    ("CONT", libc::SIGCONT),
+    ("STOP", libc::SIGSTOP),
+    ("TSTP", libc::SIGTSTP),
+    ("TTIN", libc::SIGTTIN),
+    ("TTOU", libc::SIGTTOU),
+    ("URG", libc::SIGURG),
+    ("XCPU", libc::SIGXCPU),
+    ("XFSZ", libc::SIGXFSZ),
+    ("VTALRM", libc::SIGVTALRM),
+    ("PROF", libc::SIGPROF),
+    ("WINCH", libc::SIGWINCH),
+    ("IO", libc::SIGIO),
+    ("SYS", libc::SIGSYS),
+];
+// Without signals, only the names of the signals defined by ISO C and a few common ones are known,
+// using their traditional numbers.
+#[cfg(not(target_family = "unix"))]
+const SIGNALS: &[(&str, Signal)] = &[
+    ("HUP", 1),
+    ("INT", 2),
+    ("QUIT", 3),
+    ("ILL", 4),
+    ("ABRT", 6),
+    ("FPE", 8),
+    ("KILL", 9),
+    ("SEGV", 11),
+    ("PIPE", 13),
+    ("ALRM", 14),
+    ("TERM", 15),
+];
+
+/// Signals that prcs cannot handle itself.
+#[cfg(target_family = "unix")]
+const UNCATCHABLE_SIGNALS: &[Signal] = signal_hook::consts::FORBIDDEN;
+#[cfg(not(target_family = "unix"))]
+const UNCATCHABLE_SIGNALS: &[Signal] = &[];
+
+/// Highest signal number, including real-time signals.
+#[cfg(target_os = "linux")]
+pub(crate) fn max_signal() -> Signal {
+    libc::SIGRTMAX()
+}
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn max_signal() -> Signal {
+    SIGNALS.iter().map(|(_, signal)| *signal).max().unwrap_or(0)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseSignalError<I> {
+    ParseInt(I, ParseIntError),
+    OutOfRange(I),
+    UnknownName(I),
+    Uncatchable(I),
+    MissingTranslation(I),
+}
+impl<I: std::fmt::Display> std::fmt::Display for ParseSignalError<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            ParseSignalError::ParseInt(input, err) => f.write_fmt(format_args!(
+                "Failed to parse '{}' as a signal number: {}",
+                input, err
+            )),
+            ParseSignalError::OutOfRange(input) => f.write_fmt(format_args!(
+                "Signal number {} is out of range, expected 1 to {}",
+                input,
+                max_signal()
+            )),
+            ParseSignalError::UnknownName(input) => {
+                f.write_fmt(format_args!("Unknown signal name '{}'", input))
+            }
+            ParseSignalError::Uncatchable(input) => {
+                f.write_fmt(format_args!("Signal '{}' cannot be caught by prcs", input))
+            }
+            ParseSignalError::MissingTranslation(input) => {
+                f.write_fmt(format_args!("Expected '<signal>:<signal>', got: {}", input))
+            }
+        }
+    }
+}
+
+/// Parses a signal given by name like 'TERM' or 'SIGTERM' (case-insensitive), or by number.
+pub fn parse_signal(input: &str) -> Result<Signal, ParseSignalError<&str>> {
+    let input = input.trim();
+    if input.starts_with(|c: char| c.is_ascii_digit()) {
+        let signal = input
+            .parse()
+            .map_err(|err| ParseSignalError::ParseInt(input, err))?;
+        return if (1..=max_signal()).contains(&signal) {
+            Ok(signal)
+        } else {
+            Err(ParseSignalError::OutOfRange(input))
+        };
+    }
+
+    let name = input.to_ascii_uppercase();
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+    SIGNALS
+        .iter()
+        .find(|(known_name, _)| *known_name == name)
+        .map(|(_, signal)| *signal)
+        .ok_or(ParseSignalError::UnknownName(input))
+}
+
+/// Name of the signal like 'TERM', if it is known.
+pub fn signal_name(signal: Signal) -> Option<&'static str> {
+    SIGNALS
+        .iter()
+        .find(|(_, known_signal)| *known_signal == signal)
+        .map(|(name, _)| *name)
+}
+
+/// Parses a signal like [`parse_signal`], or 0 for no signal.
+pub fn parse_signal_or_zero(input: &str) -> Result<Signal, ParseSignalError<&str>> {
+    match input.trim() {
+        "0" => Ok(0),
+        input => parse_signal(input),
+    }
+}
+
+/// Parses a signal like [`parse_signal`] that prcs can catch, unlike SIGKILL or SIGSTOP.
+pub fn parse_catchable_signal(input: &str) -> Result<Signal, ParseSignalError<&str>> {
+    let signal = parse_signal(input)?;
+    if UNCATCHABLE_SIGNALS.contains(&signal) {
+        return Err(ParseSignalError::Uncatchable(input.trim()));
+    }
+    Ok(signal)
+}
+
+/// Parses a translation from a signal that prcs catches to another given as `<signal>:<signal>`.
+pub fn parse_signal_translation(input: &str) -> Result<(Signal, Signal), ParseSignalError<&str>> {
+    let (from, to) = input
+        .split_once(':')
+        .ok_or(ParseSignalError::MissingTranslation(input))?;
+    Ok((parse_catchable_signal(from)?, parse_signal(to)?))
+}
+
+/// Process ID to send signals to in order to reach the child process, or its whole process group.
+#[cfg(target_family = "unix")]
+fn signal_target(child: &Child, process_group: bool) -> libc::pid_t {
+    let pid = child.id() as libc::pid_t;
+    if process_group {
+        -pid
+    } else {
+        pid
+    }
+}
+
+/// Sends the signal to the child process, or to its whole process group.
+/// Without signals, the child process is killed regardless of the signal.
+#[cfg(target_family = "unix")]
+pub fn send_signal(child: &mut Child, signal: Signal, process_group: bool) -> io::Result<()> {
+    if unsafe { libc::kill(signal_target(child, process_group), signal) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+#[cfg(not(target_family = "unix"))]
+pub fn send_signal(child: &mut Child, _signal: Signal, _process_group: bool) -> io::Result<()> {
+    child.kill()
+}
+
+/// Kills the child process, or its whole process group.
+#[cfg(target_family = "unix")]
+pub fn kill(child: &mut Child, process_group: bool) -> io::Result<()> {
+    send_signal(child, libc::SIGKILL, process_group)
+}
+#[cfg(not(target_family = "unix"))]
+pub fn kill(child: &mut Child, _process_group: bool) -> io::Result<()> {
+    child.kill()
+}
+
+/// Makes the child process spawned by the command receive the signal once prcs dies, unless the signal is 0.
+/// Only supported on Linux.
+#[cfg(target_os = "linux")]
+pub fn on_parent_death(command: &mut Command, signal: Signal) {
+    use std::os::unix::process::CommandExt;
+    if signal == 0 {
+        return;
+    }
+    let parent = std::process::id() as libc::pid_t;
+    unsafe {
+        command.pre_exec(move || {
+            if libc::prctl(libc::PR_SET_PDEATHSIG, signal) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // prcs may have died before the parent death signal was set up
+            if libc::getppid() != parent {
+                libc::raise(signal);
+            }
+            Ok(())
+        })
+    };
+}
+#[cfg(not(target_os = "linux"))]
+pub fn on_parent_death(_command: &mut Command, _signal: Signal) {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_catchable_signal, parse_signal, parse_signal_or_zero, parse_signal_translation,
+        signal_name,
+    };
+
+    #[test]
+    fn name() {
+        assert_eq!(parse_signal("TERM"), Ok(15));
+    }
+    #[test]
+    fn prefixed_name() {
+        assert_eq!(parse_signal("SIGINT"), parse_signal("INT"));
+    }
+    #[test]
+    fn lowercase_name() {
+        assert_eq!(parse_signal("sigkill"), Ok(9));
+    }
+    #[test]
+    fn number() {
+        assert_eq!(parse_signal("9"), Ok(9));
+    }
+
+    #[test]
+    fn known_name() {
+        assert_eq!(signal_name(15), Some("TERM"));
+    }
+    #[test]
+    fn unknown_number() {
+        assert_eq!(signal_name(0), None);
+    }
+
+    #[test]
+    fn zero() {
+        assert_eq!(parse_signal_or_zero("0"), Ok(0));
+    }
+    #[test]
+    fn non_zero() {
+        assert_eq!(parse_signal_or_zero("TERM"), Ok(15));
+    }
+
+    #[test]
+    fn catchable() {
+        assert_eq!(parse_catchable_signal("HUP"), Ok(1));
+    }
+
+    #[test]
+    fn translation() {
+        assert_eq!(parse_signal_translation("TERM:SIGINT"), Ok((15, 2)));
+    }
+    #[test]
+    fn translation_to_uncatchable() {
+        assert_eq!(parse_signal_translation("TERM:KILL"), Ok((15, 9)));
+    }
+
+    #[test]
+    fn unknown_name() {
+        insta::assert_snapshot!(parse_signal("FOO").unwrap_err().to_string(), @"Unknown signal name 'FOO'");
+    }
+    #[test]
+    fn invalid_number() {
+        insta::assert_snapshot!(parse_signal("9x").unwrap_err().to_string(), @"Failed to parse '9x' as a signal number: invalid digit found in string");
+    }
+    #[test]
+    fn zero_signal_number() {
+        insta::assert_snapshot!(parse_signal("0").unwrap_err().to_string().replace(&super::max_signal().to_string(), "<max>"), @"Signal number 0 is out of range, expected 1 to <max>");
+    }
+    #[test]
+    fn too_high_signal_number() {
+        insta::assert_snapshot!(parse_signal("99999").unwrap_err().to_string().replace(&super::max_signal().to_string(), "<max>"), @"Signal number 99999 is out of range, expected 1 to <max>");
+    }
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn uncatchable_name() {
+        insta::assert_snapshot!(parse_catchable_signal("KILL").unwrap_err().to_string(), @"Signal 'KILL' cannot be caught by prcs");
+    }
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn uncatchable_number() {
+        insta::assert_snapshot!(parse_catchable_signal("9").unwrap_err().to_string(), @"Signal '9' cannot be caught by prcs");
+    }
+    #[test]
+    fn unknown_catchable_name() {
+        insta::assert_snapshot!(parse_catchable_signal("FOO").unwrap_err().to_string(), @"Unknown signal name 'FOO'");
+    }
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn translation_from_uncatchable() {
+        insta::assert_snapshot!(parse_signal_translation("KILL:TERM").unwrap_err().to_string(), @"Signal 'KILL' cannot be caught by prcs");
+    }
+    #[test]
+    fn missing_translation() {
+        insta::assert_snapshot!(parse_signal_translation("TERM").unwrap_err().to_string(), @"Expected '<signal>:<signal>', got: TERM");
+    }
+}
@@ -1,21 +1,51 @@
+use crate::options::timeout::TIMEOUT_STATUS_CODE;
+use crate::signal::Signal;
 use crate::status_constraints::StatusCode;
 use std::process::ExitStatus;
 
+/// Status code to use when the process could not be started, like shells do for commands that are not found.
+pub const SPAWN_FAILED_STATUS_CODE: StatusCode = 127;
+
+/// How a run of the process ended.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Exited(StatusCode),
+    Signaled { signal: Signal, core_dumped: bool },
+    TimedOut,
+    SpawnFailed,
+}
+
+impl Outcome {
+    /// Status code representing the outcome, with termination by a signal represented as 128 + signal number.
+    pub fn status_code(self) -> StatusCode {
+        match self {
+            Outcome::Exited(code) => code,
+            Outcome::Signaled { signal, .. } => 128 + signal,
+            Outcome::TimedOut => TIMEOUT_STATUS_CODE,
+            Outcome::SpawnFailed => SPAWN_FAILED_STATUS_CODE,
+        }
+    }
+}
+
 #[cfg(target_family = "unix")]
-pub fn get_status_code(child_status: &ExitStatus) -> StatusCode {
+pub fn get_outcome(child_status: &ExitStatus) -> Outcome {
     use std::os::unix::prelude::ExitStatusExt;
-    child_status
-        .code()
-        .or_else(|| ExitStatusExt::signal(child_status).map(|signal| 128 + signal))
-        .unwrap_or_else(|| {
+    match (child_status.code(), child_status.signal()) {
+        (Some(code), _) => Outcome::Exited(code),
+        (None, Some(signal)) => Outcome::Signaled {
+            signal,
+            core_dumped: child_status.core_dumped(),
+        },
+        (None, None) => {
             println!("Failed to interpret child status code or signal, exiting with 128");
-            128
-        })
+            Outcome::Exited(128)
+        }
+    }
 }
 #[cfg(not(target_family = "unix"))]
-pub fn get_status_code(child_status: &ExitStatus) -> StatusCode {
-    child_status.code().unwrap_or_else(|| {
+pub fn get_outcome(child_status: &ExitStatus) -> Outcome {
+    Outcome::Exited(child_status.code().unwrap_or_else(|| {
         println!("Failed to interpret child status code, exiting with 1");
         1
-    })
+    }))
 }
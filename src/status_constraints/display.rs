@@ -0,0 +1,201 @@
+use super::{ConstraintExpression, ConstraintType, StatusConstraint};
+use crate::signal::signal_name;
+use std::fmt::{Display, Formatter, Result};
+
+impl Display for ConstraintType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ConstraintType::Lt(code) => f.write_fmt(format_args!("<{}", code)),
+            ConstraintType::Lte(code) => f.write_fmt(format_args!("<={}", code)),
+            ConstraintType::Eq(code) => f.write_fmt(format_args!("{}", code)),
+            ConstraintType::Gte(code) => f.write_fmt(format_args!(">={}", code)),
+            ConstraintType::Gt(code) => f.write_fmt(format_args!(">{}", code)),
+            ConstraintType::BitsSet(mask) => f.write_fmt(format_args!("&{}", mask)),
+            ConstraintType::Range {
+                start,
+                end,
+                inclusive,
+            } => f.write_fmt(format_args!(
+                "{}..{}{}",
+                start,
+                if *inclusive { "=" } else { "" },
+                end
+            )),
+            ConstraintType::List(codes) => f.write_str(
+                &codes
+                    .iter()
+                    .map(|code| code.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            ConstraintType::Signal(signal) => match signal_name(*signal) {
+                Some(name) => f.write_fmt(format_args!("SIG{}", name)),
+                None => f.write_fmt(format_args!("sig:{}", signal)),
+            },
+            ConstraintType::Exited => f.write_str("exited"),
+            ConstraintType::Signaled => f.write_str("signal"),
+            ConstraintType::CoreDumped => f.write_str("coredump"),
+            ConstraintType::TimedOut => f.write_str("timeout"),
+            ConstraintType::SpawnFailed => f.write_str("spawn-failed"),
+        }
+    }
+}
+
+impl Display for StatusConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.negated {
+            f.write_str("!")?;
+        }
+        self.typ.fmt(f)
+    }
+}
+
+impl Display for ConstraintExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ConstraintExpression::Constraint(constraint) => constraint.fmt(f),
+            ConstraintExpression::Not(expression) => f.write_fmt(format_args!("!({})", expression)),
+            // operands are parenthesized where parsing would otherwise combine them differently
+            ConstraintExpression::And(expressions) => {
+                write_operands(f, expressions, " & ", |operand| {
+                    matches!(
+                        operand,
+                        ConstraintExpression::And(_) | ConstraintExpression::Or(_)
+                    )
+                })
+            }
+            ConstraintExpression::Or(expressions) => {
+                write_operands(f, expressions, " | ", |operand| {
+                    matches!(operand, ConstraintExpression::Or(_))
+                })
+            }
+        }
+    }
+}
+
+fn write_operands(
+    f: &mut Formatter<'_>,
+    operands: &[ConstraintExpression],
+    separator: &str,
+    needs_parentheses: fn(&ConstraintExpression) -> bool,
+) -> Result {
+    for (i, operand) in operands.iter().enumerate() {
+        if i > 0 {
+            f.write_str(separator)?;
+        }
+        if needs_parentheses(operand) {
+            f.write_fmt(format_args!("({})", operand))?;
+        } else {
+            operand.fmt(f)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_status_constraints;
+    use super::{ConstraintExpression, ConstraintType, StatusConstraint};
+
+    fn constraint(typ: ConstraintType) -> ConstraintExpression {
+        ConstraintExpression::Constraint(StatusConstraint {
+            typ,
+            negated: false,
+        })
+    }
+
+    #[test]
+    fn negated_range() {
+        insta::assert_snapshot!(parse_status_constraints(" ! -1..10").unwrap().to_string(), @"!-1..10");
+    }
+    #[test]
+    fn signal_names() {
+        insta::assert_snapshot!(parse_status_constraints("INT | sig:15").unwrap().to_string(), @"SIGINT | SIGTERM");
+    }
+    #[test]
+    fn keywords() {
+        insta::assert_snapshot!(parse_status_constraints("exited !COREDUMP|TIMEOUT").unwrap().to_string(), @"exited & !coredump | timeout");
+    }
+    #[test]
+    fn alias() {
+        insta::assert_snapshot!(parse_status_constraints("!success").unwrap().to_string(), @"!(exited & 0)");
+    }
+    #[test]
+    fn bit_tests() {
+        insta::assert_snapshot!(parse_status_constraints("&1&&4").unwrap().to_string(), @"&1 & &4");
+    }
+    #[test]
+    fn nested_expressions() {
+        insta::assert_snapshot!(parse_status_constraints("((1 & 2) 3 | 4) & (5 | 6)").unwrap().to_string(), @"((1 & 2) & 3 | 4) & (5 | 6)");
+    }
+    #[test]
+    fn empty_expression() {
+        insta::assert_snapshot!(parse_status_constraints(" ").unwrap().to_string(), @"");
+    }
+
+    #[test]
+    fn round_trip() {
+        for input in [
+            "42",
+            "=42",
+            "-42",
+            "!42",
+            "<42",
+            ">42",
+            ">=42",
+            " ! >= -42 ",
+            "1..=10",
+            "!-1..10",
+            "1,2, 75",
+            "INT | !SIGTERM",
+            "sig:9",
+            "sigterm",
+            "!&4",
+            "&1&&4",
+            "1 & 4",
+            "1 & &4",
+            "42 1337",
+            "1 | >=2 & <=5",
+            "(>=1 & <=5) | SIGTERM",
+            "((1|2)) 3",
+            "! (1)",
+            "!(255) | 1",
+            "exited !COREDUMP | timeout | spawn-failed",
+            "signal !INT",
+            "success",
+            "Failure",
+            "!abort 1",
+            "!@ssh-transient | 1",
+            "@curl-transient",
+            " ",
+        ] {
+            let expression = parse_status_constraints(input).unwrap();
+            assert_eq!(
+                parse_status_constraints(&expression.to_string()),
+                Ok(expression),
+                "{}",
+                input
+            );
+        }
+    }
+    #[test]
+    fn round_trip_nested_operators() {
+        let expression = ConstraintExpression::And(vec![
+            ConstraintExpression::And(vec![
+                constraint(ConstraintType::Eq(1)),
+                constraint(ConstraintType::BitsSet(2)),
+            ]),
+            ConstraintExpression::Or(vec![
+                ConstraintExpression::Or(vec![
+                    constraint(ConstraintType::Exited),
+                    constraint(ConstraintType::Lt(-3)),
+                ]),
+                ConstraintExpression::Not(Box::new(constraint(ConstraintType::List(vec![4, 5])))),
+            ]),
+        ]);
+        assert_eq!(
+            parse_status_constraints(&expression.to_string()),
+            Ok(expression)
+        );
+    }
+}
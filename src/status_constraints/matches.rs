@@ -1,172 +1,465 @@
-use super::StatusConstraint;
-use super::{ConstraintType, StatusCode};
+use super::ConstraintType;
+use super::{ConstraintExpression, StatusConstraint};
+use crate::status_code::Outcome;
 
-pub trait MatchStatusCode {
-    fn matches(&self, code: StatusCode) -> bool;
+pub trait MatchOutcome {
+    fn matches(&self, outcome: Outcome) -> bool;
 }
 
-impl MatchStatusCode for StatusConstraint {
-    fn matches(&self, code: StatusCode) -> bool {
+// A process that failed to start has no status code and did not end in any other way,
+// so only 'spawn-failed' tells anything about it. Any other constraint does not match it,
+// whether negated or not, so that the default '!0' of --rerun or 'failure' do not match it either.
+
+impl StatusConstraint {
+    /// Whether a process that failed to start matches the constraint, or its negation if `negated`.
+    fn matches_spawn_failure(&self, negated: bool) -> bool {
+        self.typ == ConstraintType::SpawnFailed && self.negated == negated
+    }
+}
+impl ConstraintExpression {
+    /// Whether a process that failed to start matches the expression, or its negation if `negated`.
+    fn matches_spawn_failure(&self, negated: bool) -> bool {
+        match (self, negated) {
+            (ConstraintExpression::Constraint(constraint), _) => {
+                constraint.matches_spawn_failure(negated)
+            }
+            (ConstraintExpression::Not(expression), _) => {
+                expression.matches_spawn_failure(!negated)
+            }
+            (ConstraintExpression::And(expressions), false)
+            | (ConstraintExpression::Or(expressions), true) => expressions
+                .iter()
+                .all(|expression| expression.matches_spawn_failure(negated)),
+            (ConstraintExpression::And(expressions), true)
+            | (ConstraintExpression::Or(expressions), false) => expressions
+                .iter()
+                .any(|expression| expression.matches_spawn_failure(negated)),
+        }
+    }
+}
+
+impl MatchOutcome for StatusConstraint {
+    fn matches(&self, outcome: Outcome) -> bool {
+        if outcome == Outcome::SpawnFailed {
+            return self.matches_spawn_failure(false);
+        }
+        let code = outcome.status_code();
         (match self.typ {
-            ConstraintType::Lte => code <= self.code,
-            ConstraintType::Lt => code < self.code,
-            ConstraintType::Eq => code == self.code,
-            ConstraintType::Gt => code > self.code,
-            ConstraintType::Gte => code >= self.code,
+            ConstraintType::Lte(other) => code <= other,
+            ConstraintType::Lt(other) => code < other,
+            ConstraintType::Eq(other) => code == other,
+            ConstraintType::Gt(other) => code > other,
+            ConstraintType::Gte(other) => code >= other,
+            ConstraintType::BitsSet(mask) => code & mask == mask,
+            ConstraintType::Range {
+                start,
+                end,
+                inclusive: true,
+            } => (start..=end).contains(&code),
+            ConstraintType::Range {
+                start,
+                end,
+                inclusive: false,
+            } => (start..end).contains(&code),
+            ConstraintType::List(ref codes) => codes.contains(&code),
+            ConstraintType::Signal(signal) => matches!(
+                outcome,
+                Outcome::Signaled { signal: other, .. } if other == signal
+            ),
+            ConstraintType::Exited => matches!(outcome, Outcome::Exited(_)),
+            ConstraintType::Signaled => matches!(outcome, Outcome::Signaled { .. }),
+            ConstraintType::CoreDumped => matches!(
+                outcome,
+                Outcome::Signaled {
+                    core_dumped: true,
+                    ..
+                }
+            ),
+            ConstraintType::TimedOut => outcome == Outcome::TimedOut,
+            ConstraintType::SpawnFailed => false,
         }) ^ self.negated
     }
 }
-impl MatchStatusCode for Vec<StatusConstraint> {
-    fn matches(&self, code: StatusCode) -> bool {
-        self.iter().all(|constraint| constraint.matches(code))
+impl MatchOutcome for ConstraintExpression {
+    fn matches(&self, outcome: Outcome) -> bool {
+        if outcome == Outcome::SpawnFailed {
+            return self.matches_spawn_failure(false);
+        }
+        match self {
+            ConstraintExpression::Constraint(constraint) => constraint.matches(outcome),
+            ConstraintExpression::Not(expression) => !expression.matches(outcome),
+            ConstraintExpression::And(expressions) => expressions
+                .iter()
+                .all(|expression| expression.matches(outcome)),
+            ConstraintExpression::Or(expressions) => expressions
+                .iter()
+                .any(|expression| expression.matches(outcome)),
+        }
     }
 }
-impl MatchStatusCode for Vec<Vec<StatusConstraint>> {
-    fn matches(&self, code: StatusCode) -> bool {
-        self.iter().any(|constraints| constraints.matches(code))
+impl MatchOutcome for Vec<ConstraintExpression> {
+    fn matches(&self, outcome: Outcome) -> bool {
+        self.iter().any(|expression| expression.matches(outcome))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ConstraintType, MatchStatusCode, StatusConstraint};
+    use super::super::parse_status_constraints;
+    use super::{ConstraintExpression, ConstraintType, MatchOutcome, StatusConstraint};
+    use crate::status_code::Outcome;
 
     #[test]
     fn lt_match() {
         assert!(StatusConstraint {
-            typ: ConstraintType::Lt,
-            code: 42,
+            typ: ConstraintType::Lt(42),
             negated: false
         }
-        .matches(41))
+        .matches(Outcome::Exited(41)))
     }
     #[test]
     fn lt_mismatch() {
         assert!(!StatusConstraint {
-            typ: ConstraintType::Lt,
-            code: 42,
+            typ: ConstraintType::Lt(42),
             negated: false
         }
-        .matches(42))
+        .matches(Outcome::Exited(42)))
     }
 
     #[test]
     fn lte_match() {
         assert!(StatusConstraint {
-            typ: ConstraintType::Lte,
-            code: 42,
+            typ: ConstraintType::Lte(42),
             negated: false
         }
-        .matches(42))
+        .matches(Outcome::Exited(42)))
     }
     #[test]
     fn lte_mismatch() {
         assert!(!StatusConstraint {
-            typ: ConstraintType::Lte,
-            code: 42,
+            typ: ConstraintType::Lte(42),
             negated: false
         }
-        .matches(43))
+        .matches(Outcome::Exited(43)))
     }
 
     #[test]
     fn eq_match() {
         assert!(StatusConstraint {
-            typ: ConstraintType::Eq,
-            code: 42,
+            typ: ConstraintType::Eq(42),
             negated: false
         }
-        .matches(42))
+        .matches(Outcome::Exited(42)))
     }
     #[test]
     fn eq_mismatch() {
         assert!(!StatusConstraint {
-            typ: ConstraintType::Eq,
-            code: 42,
+            typ: ConstraintType::Eq(42),
             negated: false
         }
-        .matches(1337))
+        .matches(Outcome::Exited(1337)))
     }
 
     #[test]
     fn gt_match() {
         assert!(StatusConstraint {
-            typ: ConstraintType::Gt,
-            code: 42,
+            typ: ConstraintType::Gt(42),
             negated: false
         }
-        .matches(43))
+        .matches(Outcome::Exited(43)))
     }
     #[test]
     fn gt_mismatch() {
         assert!(!StatusConstraint {
-            typ: ConstraintType::Gt,
-            code: 42,
+            typ: ConstraintType::Gt(42),
             negated: false
         }
-        .matches(42))
+        .matches(Outcome::Exited(42)))
     }
 
     #[test]
     fn gte_match() {
         assert!(StatusConstraint {
-            typ: ConstraintType::Gte,
-            code: 42,
+            typ: ConstraintType::Gte(42),
             negated: false
         }
-        .matches(42))
+        .matches(Outcome::Exited(42)))
     }
     #[test]
     fn gte_mismatch() {
         assert!(!StatusConstraint {
-            typ: ConstraintType::Gte,
-            code: 42,
+            typ: ConstraintType::Gte(42),
+            negated: false
+        }
+        .matches(Outcome::Exited(41)))
+    }
+
+    #[test]
+    fn bits_set_match() {
+        assert!(StatusConstraint {
+            typ: ConstraintType::BitsSet(6),
+            negated: false
+        }
+        .matches(Outcome::Exited(7)))
+    }
+    #[test]
+    fn bits_set_mismatch() {
+        assert!(!StatusConstraint {
+            typ: ConstraintType::BitsSet(6),
+            negated: false
+        }
+        .matches(Outcome::Exited(4)))
+    }
+
+    #[test]
+    fn inclusive_range_match() {
+        assert!(StatusConstraint {
+            typ: ConstraintType::Range {
+                start: 1,
+                end: 10,
+                inclusive: true
+            },
+            negated: false
+        }
+        .matches(Outcome::Exited(10)))
+    }
+    #[test]
+    fn inclusive_range_mismatch() {
+        assert!(!StatusConstraint {
+            typ: ConstraintType::Range {
+                start: 1,
+                end: 10,
+                inclusive: true
+            },
+            negated: false
+        }
+        .matches(Outcome::Exited(0)))
+    }
+    #[test]
+    fn exclusive_range_match() {
+        assert!(StatusConstraint {
+            typ: ConstraintType::Range {
+                start: 1,
+                end: 10,
+                inclusive: false
+            },
+            negated: false
+        }
+        .matches(Outcome::Exited(1)))
+    }
+    #[test]
+    fn exclusive_range_mismatch() {
+        assert!(!StatusConstraint {
+            typ: ConstraintType::Range {
+                start: 1,
+                end: 10,
+                inclusive: false
+            },
+            negated: false
+        }
+        .matches(Outcome::Exited(10)))
+    }
+
+    #[test]
+    fn list_match() {
+        assert!(StatusConstraint {
+            typ: ConstraintType::List(vec![1, 2, 75]),
+            negated: false
+        }
+        .matches(Outcome::Exited(75)))
+    }
+    #[test]
+    fn list_mismatch() {
+        assert!(!StatusConstraint {
+            typ: ConstraintType::List(vec![1, 2, 75]),
             negated: false
         }
-        .matches(41))
+        .matches(Outcome::Exited(3)))
+    }
+
+    fn constraint(typ: ConstraintType) -> StatusConstraint {
+        StatusConstraint {
+            typ,
+            negated: false,
+        }
+    }
+    const SIGINT: Outcome = Outcome::Signaled {
+        signal: 2,
+        core_dumped: false,
+    };
+    const SIGABRT_CORE_DUMPED: Outcome = Outcome::Signaled {
+        signal: 6,
+        core_dumped: true,
+    };
+
+    #[test]
+    fn code_matches_signal() {
+        assert!(constraint(ConstraintType::Eq(130)).matches(SIGINT))
+    }
+    #[test]
+    fn signal_match() {
+        assert!(constraint(ConstraintType::Signal(2)).matches(SIGINT))
+    }
+    #[test]
+    fn signal_mismatch() {
+        assert!(!constraint(ConstraintType::Signal(6)).matches(SIGINT))
+    }
+    #[test]
+    fn signal_mismatches_code() {
+        assert!(!constraint(ConstraintType::Signal(2)).matches(Outcome::Exited(130)))
+    }
+    #[test]
+    fn exited_match() {
+        assert!(constraint(ConstraintType::Exited).matches(Outcome::Exited(130)))
+    }
+    #[test]
+    fn exited_mismatch() {
+        assert!(!constraint(ConstraintType::Exited).matches(SIGINT))
+    }
+    #[test]
+    fn signaled_match() {
+        assert!(constraint(ConstraintType::Signaled).matches(SIGINT))
+    }
+    #[test]
+    fn signaled_mismatch() {
+        assert!(!constraint(ConstraintType::Signaled).matches(Outcome::Exited(130)))
+    }
+    #[test]
+    fn core_dumped_match() {
+        assert!(constraint(ConstraintType::CoreDumped).matches(SIGABRT_CORE_DUMPED))
+    }
+    #[test]
+    fn core_dumped_mismatch() {
+        assert!(!constraint(ConstraintType::CoreDumped).matches(SIGINT))
+    }
+    #[test]
+    fn timed_out_match() {
+        assert!(constraint(ConstraintType::TimedOut).matches(Outcome::TimedOut))
+    }
+    #[test]
+    fn timed_out_mismatch() {
+        assert!(!constraint(ConstraintType::TimedOut).matches(Outcome::Exited(124)))
+    }
+    #[test]
+    fn spawn_failed_match() {
+        assert!(constraint(ConstraintType::SpawnFailed).matches(Outcome::SpawnFailed))
+    }
+    #[test]
+    fn spawn_failed_mismatch() {
+        assert!(!constraint(ConstraintType::SpawnFailed).matches(Outcome::Exited(127)))
+    }
+
+    #[test]
+    fn code_mismatches_spawn_failure() {
+        assert!(!constraint(ConstraintType::Eq(127)).matches(Outcome::SpawnFailed))
+    }
+    #[test]
+    fn negated_code_mismatches_spawn_failure() {
+        assert!(!StatusConstraint {
+            typ: ConstraintType::Eq(0),
+            negated: true
+        }
+        .matches(Outcome::SpawnFailed))
+    }
+    #[test]
+    fn negated_expression_mismatches_spawn_failure() {
+        for input in [
+            "!(0)",
+            "failure",
+            "!SIGINT",
+            "!exited",
+            "!spawn-failed",
+            "!(1 & spawn-failed)",
+        ] {
+            assert!(
+                !parse_status_constraints(input)
+                    .unwrap()
+                    .matches(Outcome::SpawnFailed),
+                "{}",
+                input
+            )
+        }
+    }
+    #[test]
+    fn expression_with_spawn_failed_matches_spawn_failure() {
+        for input in [
+            "spawn-failed",
+            "1 | spawn-failed",
+            "!(!spawn-failed)",
+            "!(1 & !spawn-failed)",
+        ] {
+            assert!(
+                parse_status_constraints(input)
+                    .unwrap()
+                    .matches(Outcome::SpawnFailed),
+                "{}",
+                input
+            )
+        }
     }
 
     #[test]
     fn negation() {
         assert!(StatusConstraint {
-            typ: ConstraintType::Eq,
-            code: 42,
+            typ: ConstraintType::Eq(42),
             negated: true
         }
-        .matches(1337))
+        .matches(Outcome::Exited(1337)))
     }
 
     #[test]
-    fn multiple_constraints() {
-        assert!(!vec![
-            StatusConstraint {
-                typ: ConstraintType::Eq,
-                code: 42,
+    fn and() {
+        assert!(!ConstraintExpression::And(vec![
+            ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Eq(42),
                 negated: false
-            },
-            StatusConstraint {
-                typ: ConstraintType::Eq,
-                code: 1337,
+            }),
+            ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Eq(1337),
                 negated: false
-            }
-        ]
-        .matches(42))
+            })
+        ])
+        .matches(Outcome::Exited(42)))
+    }
+    #[test]
+    fn or() {
+        assert!(ConstraintExpression::Or(vec![
+            ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Eq(42),
+                negated: false
+            }),
+            ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Eq(1337),
+                negated: false
+            })
+        ])
+        .matches(Outcome::Exited(42)))
+    }
+    #[test]
+    fn not() {
+        assert!(
+            !ConstraintExpression::Not(Box::new(ConstraintExpression::Constraint(
+                StatusConstraint {
+                    typ: ConstraintType::Eq(42),
+                    negated: false
+                }
+            )))
+            .matches(Outcome::Exited(42))
+        )
     }
 
     #[test]
     fn constraint_options() {
         assert!(vec![
-            vec![StatusConstraint {
-                typ: ConstraintType::Eq,
-                code: 42,
+            ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Eq(42),
                 negated: false
-            }],
-            vec![StatusConstraint {
-                typ: ConstraintType::Eq,
-                code: 1337,
+            }),
+            ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Eq(1337),
                 negated: false
-            }]
+            })
         ]
-        .matches(42))
+        .matches(Outcome::Exited(42)))
     }
 }
@@ -1,22 +1,51 @@
+mod display;
 mod matches;
 mod parse;
+#[cfg(feature = "serde")]
+mod serialize;
+mod sets;
+
+use crate::signal::Signal;
 
 pub type StatusCode = i32;
 
 #[derive(Debug, PartialEq)]
 pub enum ConstraintType {
-    Lt,
-    Lte,
-    Eq,
-    Gte,
-    Gt,
+    Lt(StatusCode),
+    Lte(StatusCode),
+    Eq(StatusCode),
+    Gte(StatusCode),
+    Gt(StatusCode),
+    /// All bits of the mask are set.
+    BitsSet(StatusCode),
+    Range {
+        start: StatusCode,
+        end: StatusCode,
+        inclusive: bool,
+    },
+    List(Vec<StatusCode>),
+    /// Terminated by the signal, as opposed to exiting with 128 + signal number.
+    Signal(Signal),
+    Exited,
+    Signaled,
+    CoreDumped,
+    TimedOut,
+    SpawnFailed,
 }
 #[derive(Debug, PartialEq)]
 pub struct StatusConstraint {
     typ: ConstraintType,
-    code: StatusCode,
     negated: bool,
 }
 
-pub use matches::MatchStatusCode;
-pub use parse::parse_status_constraints;
+/// Status constraints combined with `&`, `|`, `!` and parentheses.
+#[derive(Debug, PartialEq)]
+pub enum ConstraintExpression {
+    Constraint(StatusConstraint),
+    Not(Box<ConstraintExpression>),
+    And(Vec<ConstraintExpression>),
+    Or(Vec<ConstraintExpression>),
+}
+
+pub use matches::MatchOutcome;
+pub use parse::{parse_status_constraints, ParseStatusConstraintsError};
@@ -1,6 +1,7 @@
-use super::{ConstraintType, StatusConstraint};
-use nom::combinator::eof;
-use nom::multi::many_till;
+use super::sets::{self, BUILTIN_SETS};
+use super::{ConstraintExpression, ConstraintType, StatusCode, StatusConstraint};
+use crate::signal::{parse_signal, ParseSignalError, Signal};
+use nom::bytes::complete::{tag_no_case, take_while1};
 use nom::{
     character::complete::{char, digit1, one_of, space0},
     combinator::{opt, recognize},
@@ -8,73 +9,324 @@ use nom::{
     sequence::tuple,
     Parser,
 };
-use std::{i32, num::ParseIntError, str::FromStr};
-
-// https://github.com/rust-lang/rust/issues/22639
-fn i32_pos_overflow_error() -> ParseIntError {
-    i32::from_str(&i64::max_value().to_string()).unwrap_err()
-}
-fn i32_neg_overflow_error() -> ParseIntError {
-    i32::from_str(&i64::min_value().to_string()).unwrap_err()
-}
+use std::num::{IntErrorKind, ParseIntError};
+use std::{cell::Cell, str::FromStr};
 
+/// What went wrong parsing status constraints, at the part of the input that could not be parsed.
 #[derive(Debug, PartialEq)]
-pub enum ParseStatusConstraintsError<I> {
+pub enum ParseErrorKind<I> {
     ParseInt(I, ParseIntError),
-    UnexpectedToken { allowed_tokens: String, input: I },
+    UnexpectedToken {
+        allowed_tokens: String,
+        name_allowed: bool,
+        input: I,
+    },
+    UnknownSignal(I),
+    SignalOutOfRange(I),
+    AmbiguousBitTest(I),
+    UnknownSet(I),
+    InvalidSets(I, &'static str),
+    NestedTooDeeply(I),
+    InSet(I, Box<ParseErrorKind<&'static str>>),
     UnknownError(I, ErrorKind),
 }
-impl<I> ParseError<I> for ParseStatusConstraintsError<I> {
+impl<I> ParseError<I> for ParseErrorKind<I> {
     fn from_error_kind(input: I, kind: nom::error::ErrorKind) -> Self {
-        ParseStatusConstraintsError::UnknownError(input, kind)
+        ParseErrorKind::UnknownError(input, kind)
     }
 
     fn append(_: I, _: nom::error::ErrorKind, other: Self) -> Self {
         other
     }
 }
-impl<I: std::fmt::Display> std::fmt::Display for ParseStatusConstraintsError<I> {
+impl std::fmt::Display for ParseErrorKind<&str> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         match self {
-            ParseStatusConstraintsError::ParseInt(input, parse_int_err)
-                if *parse_int_err == i32_pos_overflow_error() =>
-            {
-                f.write_fmt(format_args!(
+            ParseErrorKind::ParseInt(input, parse_int_err) => match parse_int_err.kind() {
+                IntErrorKind::PosOverflow => f.write_fmt(format_args!(
                     "Failed to parse '{}' as a status code. Integer too high.",
                     input
-                ))
-            }
-            ParseStatusConstraintsError::ParseInt(input, parse_int_err)
-                if *parse_int_err == i32_neg_overflow_error() =>
-            {
-                f.write_fmt(format_args!(
+                )),
+                IntErrorKind::NegOverflow => f.write_fmt(format_args!(
                     "Failed to parse '{}' as a status code. Integer too low.",
                     input
-                ))
-            }
-            ParseStatusConstraintsError::ParseInt(input, parse_int_err) => {
-                f.write_fmt(format_args!(
+                )),
+                _ => f.write_fmt(format_args!(
                     "Failed to parse '{}' as a status code. Unknown error: {}",
                     input, parse_int_err
-                ))
-            }
-            ParseStatusConstraintsError::UnexpectedToken {
+                )),
+            },
+            ParseErrorKind::UnexpectedToken {
                 allowed_tokens,
+                name_allowed,
                 input,
-            } => f.write_fmt(format_args!(
-                "Expected one of '{}', got: {}",
-                allowed_tokens, input
+            } => {
+                f.write_fmt(format_args!(
+                    "Expected one of '{}'{}, ",
+                    allowed_tokens,
+                    if *name_allowed { " or a name" } else { "" }
+                ))?;
+                match *input {
+                    "" => f.write_str("got end of input"),
+                    input => f.write_fmt(format_args!("got: {}", input)),
+                }
+            }
+            ParseErrorKind::UnknownSignal(input) => {
+                f.write_fmt(format_args!("Unknown signal name '{}'", input))
+            }
+            ParseErrorKind::SignalOutOfRange(input) => ParseSignalError::OutOfRange(input).fmt(f),
+            ParseErrorKind::AmbiguousBitTest(input) => f.write_fmt(format_args!(
+                "Ambiguous bit test '{}' after another constraint",
+                input
             )),
-            ParseStatusConstraintsError::UnknownError(input, _kind) => {
+            ParseErrorKind::UnknownSet(input) => {
+                f.write_fmt(format_args!("Unknown constraint set '@{}'", input))
+            }
+            ParseErrorKind::InvalidSets(_, err) => f.write_str(err),
+            ParseErrorKind::NestedTooDeeply(input) => f.write_fmt(format_args!(
+                "Constraint set '@{}' is nested too deeply, does it refer to itself?",
+                input
+            )),
+            ParseErrorKind::InSet(input, err) => {
+                f.write_fmt(format_args!("In constraint set '@{}': {}", input, err))
+            }
+            ParseErrorKind::UnknownError(input, _kind) => {
                 f.write_fmt(format_args!("Unknown parse error at '{}'", input))
             }
         }
     }
 }
 
+const SYNTAX_HINT: &str = "Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', \
+    and can be combined with '&', '|', '!' and parentheses.";
+
+impl<I> ParseErrorKind<I> {
+    /// Part of the input that could not be parsed.
+    fn input(&self) -> &I {
+        match self {
+            ParseErrorKind::ParseInt(input, _)
+            | ParseErrorKind::UnexpectedToken { input, .. }
+            | ParseErrorKind::UnknownSignal(input)
+            | ParseErrorKind::SignalOutOfRange(input)
+            | ParseErrorKind::AmbiguousBitTest(input)
+            | ParseErrorKind::UnknownSet(input)
+            | ParseErrorKind::InvalidSets(input, _)
+            | ParseErrorKind::NestedTooDeeply(input)
+            | ParseErrorKind::InSet(input, _)
+            | ParseErrorKind::UnknownError(input, _) => input,
+        }
+    }
+
+    fn hint(&self) -> Option<String> {
+        match self {
+            ParseErrorKind::ParseInt(_, _) => Some(format!(
+                "Status codes range from {} to {}.",
+                StatusCode::MIN,
+                StatusCode::MAX
+            )),
+            ParseErrorKind::UnexpectedToken { .. } | ParseErrorKind::UnknownError(_, _) => {
+                Some(SYNTAX_HINT.to_string())
+            }
+            ParseErrorKind::UnknownSignal(_) => Some(
+                "Signal names look like 'TERM', 'SIGTERM' or 'sig:15'. \
+                Keywords are 'exited', 'signal', 'coredump', 'timeout' and 'spawn-failed', aliases are 'success', 'failure' and 'abort'."
+                    .to_string(),
+            ),
+            ParseErrorKind::AmbiguousBitTest(_) => Some(
+                "After a constraint, '&' combines it with the next one, like '1 & 4' for status 1 and 4. \
+                Use '1 & &4' to test the bits of the mask 4 instead."
+                    .to_string(),
+            ),
+            ParseErrorKind::UnknownSet(_) => Some(format!(
+                "Built-in sets are {}. More sets can be defined in the file at $PRCS_CONSTRAINT_SETS or $XDG_CONFIG_HOME/prcs/constraint-sets.",
+                BUILTIN_SETS
+                    .iter()
+                    .map(|(name, _)| format!("'@{}'", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            ParseErrorKind::InvalidSets(_, _) => {
+                Some("Sets are defined as lines of '<name> = <constraints>'.".to_string())
+            }
+            ParseErrorKind::SignalOutOfRange(_) | ParseErrorKind::NestedTooDeeply(_) => None,
+            ParseErrorKind::InSet(_, err) => err.hint(),
+        }
+    }
+}
+
+/// Error parsing status constraints, which points at where in the input it occurred.
+#[derive(Debug, PartialEq)]
+pub struct ParseStatusConstraintsError<I> {
+    input: I,
+    kind: ParseErrorKind<I>,
+}
+impl std::fmt::Display for ParseStatusConstraintsError<&str> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        self.kind.fmt(f)?;
+
+        // parts of constraint sets are not part of the input, so there is nothing to point at for them
+        let start = self.input.as_ptr() as usize;
+        let error_start = self.kind.input().as_ptr() as usize;
+        if (start..=start + self.input.len()).contains(&error_start) {
+            let column = self.input[..error_start - start].chars().count();
+            let width = match self.kind {
+                ParseErrorKind::UnexpectedToken { .. } | ParseErrorKind::UnknownError(_, _) => 1,
+                _ => self.kind.input().chars().count().max(1),
+            };
+            f.write_fmt(format_args!(
+                "\n  {}\n  {}{}",
+                self.input,
+                " ".repeat(column),
+                "^".repeat(width)
+            ))?;
+        }
+
+        match self.kind.hint() {
+            Some(hint) => f.write_fmt(format_args!("\nHint: {}", hint)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Keyword matching how the process ended rather than its status code, or an alias for constraints.
+fn keyword(name: &str) -> Option<Name> {
+    match name.to_ascii_lowercase().as_str() {
+        "exited" => Some(Name::Keyword(ConstraintType::Exited)),
+        "signal" => Some(Name::Keyword(ConstraintType::Signaled)),
+        "coredump" => Some(Name::Keyword(ConstraintType::CoreDumped)),
+        "timeout" => Some(Name::Keyword(ConstraintType::TimedOut)),
+        "spawn-failed" => Some(Name::Keyword(ConstraintType::SpawnFailed)),
+        "success" => Some(Name::Alias("exited & 0")),
+        "failure" => Some(Name::Alias("!success")),
+        // like systemd, which considers these signals a clean exit
+        "abort" => Some(Name::Alias("signal !HUP !INT !TERM !PIPE")),
+        _ => None,
+    }
+}
+
+enum Name {
+    Signal(Signal),
+    Keyword(ConstraintType),
+    Alias(&'static str),
+}
+
+/// Parses a signal name given as `[sig:]<name>` or a keyword, if the input starts with one.
+fn parse_name(input: &str) -> Result<(&str, Option<Name>), nom::Err<ParseErrorKind<&str>>> {
+    let (input, prefixed) = opt(tag_no_case("sig:"))
+        .map(|prefix| prefix.is_some())
+        .parse(input)?;
+    let name =
+        take_while1::<_, _, ()>(|c: char| c.is_ascii_alphanumeric() || (!prefixed && c == '-'))
+            .parse(input);
+    let (input, name) = match name {
+        Ok((_, name)) if !prefixed && !name.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+            return Ok((input, None))
+        }
+        Ok(result) => result,
+        Err(_) if prefixed => return Err(nom::Err::Error(ParseErrorKind::UnknownSignal(input))),
+        Err(_) => return Ok((input, None)),
+    };
+
+    if let Some(keyword) = keyword(name).filter(|_| !prefixed) {
+        return Ok((input, Some(keyword)));
+    }
+    match parse_signal(name) {
+        Ok(signal) => Ok((input, Some(Name::Signal(signal)))),
+        Err(ParseSignalError::OutOfRange(_)) => {
+            Err(nom::Err::Error(ParseErrorKind::SignalOutOfRange(name)))
+        }
+        Err(ParseSignalError::ParseInt(_, err)) if *err.kind() == IntErrorKind::PosOverflow => {
+            Err(nom::Err::Error(ParseErrorKind::SignalOutOfRange(name)))
+        }
+        Err(_) => Err(nom::Err::Error(ParseErrorKind::UnknownSignal(name))),
+    }
+}
+
+/// Parses a status code given as a number.
+fn parse_code(
+    input: &str,
+    mut allowed_tokens: String,
+    name_allowed: bool,
+) -> Result<(&str, StatusCode), nom::Err<ParseErrorKind<&str>>> {
+    let (input, code) = recognize(tuple((opt(char('-')), digit1)))
+        .parse(input)
+        .map_err(move |_: nom::Err<()>| {
+            let input_after_minus = input.strip_prefix('-');
+
+            if input_after_minus.is_some() {
+                allowed_tokens.clear();
+            } else {
+                allowed_tokens.push('-');
+            }
+            allowed_tokens += "0123456789";
+
+            nom::Err::Error(ParseErrorKind::UnexpectedToken {
+                allowed_tokens,
+                name_allowed: name_allowed && input_after_minus.is_none(),
+                input: input_after_minus.unwrap_or(input),
+            })
+        })?;
+    Ok((
+        input,
+        i32::from_str(code).map_err(|err| nom::Err::Error(ParseErrorKind::ParseInt(code, err)))?,
+    ))
+}
+
+/// Parses a status code given as a number, where a name is not allowed.
+fn parse_value(
+    input: &str,
+    allowed_tokens: String,
+) -> Result<(&str, StatusCode), nom::Err<ParseErrorKind<&str>>> {
+    match parse_name(input)? {
+        // signals, keywords and aliases are not status codes to compare with
+        (_, Some(_)) => Err(nom::Err::Error(ParseErrorKind::UnexpectedToken {
+            allowed_tokens: allowed_tokens + "-0123456789",
+            name_allowed: false,
+            input,
+        })),
+        (input, None) => parse_code(input, allowed_tokens, false),
+    }
+}
+
+/// Parses the rest of a range given as `<start>..[=]<end>` or a list given as `<code>,<code>...`,
+/// or nothing for a single status code.
+fn parse_range_or_list(
+    input: &str,
+    start: StatusCode,
+) -> Result<(&str, ConstraintType), nom::Err<ParseErrorKind<&str>>> {
+    if let Some(input) = input.strip_prefix("..") {
+        let (input, inclusive) = opt(char('='))
+            .map(|equal_sign| equal_sign.is_some())
+            .parse(input)?;
+        let allowed_tokens = if inclusive { "" } else { "=" };
+        let (input, end) = parse_value(input, allowed_tokens.to_string())?;
+        return Ok((
+            input,
+            ConstraintType::Range {
+                start,
+                end,
+                inclusive,
+            },
+        ));
+    }
+
+    let mut input = input;
+    let mut codes = vec![start];
+    while let Some(rest) = input.strip_prefix(',') {
+        let (rest, _) = space0(rest)?;
+        let (rest, code) = parse_value(rest, String::new())?;
+        input = rest;
+        codes.push(code);
+    }
+    match codes[..] {
+        [code] => Ok((input, ConstraintType::Eq(code))),
+        _ => Ok((input, ConstraintType::List(codes))),
+    }
+}
+
 fn parse_status_constraint(
     input: &str,
-) -> Result<(&str, StatusConstraint), nom::Err<ParseStatusConstraintsError<&str>>> {
+) -> Result<(&str, StatusConstraint), nom::Err<ParseErrorKind<&str>>> {
     let mut allowed_tokens = String::new();
     let (input, _) = space0(input)?;
 
@@ -89,77 +341,232 @@ fn parse_status_constraint(
 
     let (input, _) = space0(input)?;
 
-    let (input, operator) = opt(one_of("><")).parse(input)?;
+    let (input, operator) = opt(one_of("><&")).parse(input)?;
     if operator.is_some() {
         allowed_tokens.clear();
     } else {
-        allowed_tokens.push_str("><")
+        allowed_tokens.push_str("><&")
     }
 
-    let (input, allow_equal) = opt(char('='))
-        .map(|equal_sign| equal_sign.is_some())
-        .parse(input)?;
+    let (input, allow_equal) = match operator {
+        Some('&') => (input, false),
+        _ => opt(char('='))
+            .map(|equal_sign| equal_sign.is_some())
+            .parse(input)?,
+    };
     if allow_equal {
         allowed_tokens.clear();
-    } else {
+    } else if operator != Some('&') {
         allowed_tokens.push('=')
     }
 
     let (input, _) = space0(input)?;
 
-    let (input, code) = recognize(tuple((opt(char('-')), digit1)))
-        .parse(input)
-        .map_err(move |_: nom::Err<()>| {
-            let input_after_minus = input.strip_prefix('-');
+    let name_allowed = operator.is_none() && !allow_equal;
+    if name_allowed {
+        let typ = match parse_name(input)? {
+            (input, Some(Name::Keyword(typ))) => Some((input, typ)),
+            (input, Some(Name::Signal(signal))) => Some((input, ConstraintType::Signal(signal))),
+            _ => None,
+        };
+        if let Some((input, typ)) = typ {
+            let (input, _) = space0(input)?;
+            return Ok((input, StatusConstraint { typ, negated }));
+        }
+        // parentheses, sets and aliases are parsed as operands before getting here
+        allowed_tokens.push_str("(@");
+    }
 
-            if input_after_minus.is_some() {
-                allowed_tokens.clear();
-            } else {
-                allowed_tokens.push('-');
-            }
-            allowed_tokens += "0123456789";
+    let (input, code) = if name_allowed {
+        parse_code(input, allowed_tokens, true)?
+    } else {
+        parse_value(input, allowed_tokens)?
+    };
+    let (input, typ) = match (operator, allow_equal) {
+        (Some('>'), true) => (input, ConstraintType::Gte(code)),
+        (Some('>'), false) => (input, ConstraintType::Gt(code)),
+        (Some('<'), true) => (input, ConstraintType::Lte(code)),
+        (Some('<'), false) => (input, ConstraintType::Lt(code)),
+        (Some('&'), _) => (input, ConstraintType::BitsSet(code)),
+        _ => parse_range_or_list(input, code)?,
+    };
 
-            nom::Err::Error(ParseStatusConstraintsError::UnexpectedToken {
-                allowed_tokens,
-                input: input_after_minus.unwrap_or(input),
-            })
-        })?;
+    let (input, _) = space0(input)?;
 
+    Ok((input, StatusConstraint { typ, negated }))
+}
+/// Parses a constraint, or an alias, constraint set or expression in parentheses
+/// given as `[!]<alias>`, `[!]@<set>` or `[!](<expression>)`, with any number of negations.
+fn parse_operand(
+    input: &str,
+) -> Result<(&str, ConstraintExpression), nom::Err<ParseErrorKind<&str>>> {
     let (input, _) = space0(input)?;
+    let (rest, negated) = opt(char('!'))
+        .map(|exclamation_mark| exclamation_mark.is_some())
+        .parse(input)?;
+    let (rest, _) = space0(rest)?;
+
+    let (rest, expression) = match (
+        rest.strip_prefix('('),
+        rest.strip_prefix('@'),
+        parse_name(rest)?,
+    ) {
+        _ if negated && rest.starts_with('!') => parse_operand(rest)?,
+        (Some(rest), _, _) => {
+            let (rest, expression) = parse_or(rest)?;
+            let rest = rest.strip_prefix(')').ok_or_else(|| {
+                nom::Err::Error(ParseErrorKind::UnexpectedToken {
+                    allowed_tokens: "&|)".to_string(),
+                    name_allowed: false,
+                    input: rest,
+                })
+            })?;
+            (rest, expression)
+        }
+        (None, Some(rest), _) => {
+            let (rest, name) =
+                take_while1(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                    .parse(rest)
+                    .map_err(|_: nom::Err<()>| nom::Err::Error(ParseErrorKind::UnknownSet(rest)))?;
+            (rest, parse_set(name)?)
+        }
+        (None, None, (rest, Some(Name::Alias(alias)))) => (rest, parse_definition(alias)?),
+        (None, None, _) => {
+            return parse_status_constraint(input)
+                .map(|(input, constraint)| (input, ConstraintExpression::Constraint(constraint)))
+        }
+    };
+    let (rest, _) = space0(rest)?;
 
     Ok((
-        input,
-        StatusConstraint {
-            typ: match (operator, allow_equal) {
-                (Some('>'), true) => ConstraintType::Gte,
-                (Some('>'), false) => ConstraintType::Gt,
-                (Some('<'), true) => ConstraintType::Lte,
-                (Some('<'), false) => ConstraintType::Lt,
-                _ => ConstraintType::Eq,
-            },
-            code: i32::from_str(code)
-                .map_err(|err| nom::Err::Error(ParseStatusConstraintsError::ParseInt(code, err)))?,
-            negated,
+        rest,
+        if negated {
+            ConstraintExpression::Not(Box::new(expression))
+        } else {
+            expression
         },
     ))
 }
+
+/// Parses the constraints that an alias or constraint set stands for.
+fn parse_definition(
+    definition: &'static str,
+) -> Result<ConstraintExpression, nom::Err<ParseErrorKind<&'static str>>> {
+    match parse_or(definition)? {
+        ("", expression) => Ok(expression),
+        (input, _) => Err(nom::Err::Error(ParseErrorKind::UnexpectedToken {
+            allowed_tokens: "&|".to_string(),
+            name_allowed: false,
+            input,
+        })),
+    }
+}
+
+/// How many constraint sets may be nested in each other, to stop sets that refer to themselves.
+const MAX_SET_DEPTH: u32 = 16;
+
+fn parse_set(name: &str) -> Result<ConstraintExpression, nom::Err<ParseErrorKind<&str>>> {
+    thread_local!(static DEPTH: Cell<u32> = const { Cell::new(0) });
+
+    let definition = sets::lookup(name)
+        .map_err(|err| nom::Err::Error(ParseErrorKind::InvalidSets(name, err)))?
+        .ok_or(nom::Err::Error(ParseErrorKind::UnknownSet(name)))?;
+    if DEPTH.get() >= MAX_SET_DEPTH {
+        return Err(nom::Err::Error(ParseErrorKind::NestedTooDeeply(name)));
+    }
+    DEPTH.set(DEPTH.get() + 1);
+    let expression = parse_definition(definition);
+    DEPTH.set(DEPTH.get() - 1);
+    expression.map_err(|err| {
+        err.map(|kind| match kind {
+            ParseErrorKind::NestedTooDeeply(_) => ParseErrorKind::NestedTooDeeply(name),
+            kind => ParseErrorKind::InSet(name, Box::new(kind)),
+        })
+    })
+}
+
+/// Parses operands separated by `&`, whitespace or nothing at all.
+fn parse_and(input: &str) -> Result<(&str, ConstraintExpression), nom::Err<ParseErrorKind<&str>>> {
+    let (mut input, operand) = parse_operand(input)?;
+    let mut operands = vec![operand];
+    loop {
+        input = match input.strip_prefix('&') {
+            // '&' directly followed by a number could mean either a bit test or combining operands
+            Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit() || c == '-') => {
+                let mask = rest.strip_prefix('-').unwrap_or(rest);
+                let end = input.len() - mask.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                return Err(nom::Err::Error(ParseErrorKind::AmbiguousBitTest(
+                    &input[..end],
+                )));
+            }
+            Some(rest) => rest,
+            // adjacent operands are implicitly combined with '&'
+            None if input
+                .starts_with(|c: char| c.is_ascii_alphanumeric() || "!<>=-(@".contains(c)) =>
+            {
+                input
+            }
+            None => break,
+        };
+        let (rest, operand) = parse_operand(input)?;
+        input = rest;
+        operands.push(operand);
+    }
+
+    Ok((input, combine(operands, ConstraintExpression::And)))
+}
+
+/// Parses operands separated by `|`, which binds weaker than `&`.
+fn parse_or(input: &str) -> Result<(&str, ConstraintExpression), nom::Err<ParseErrorKind<&str>>> {
+    let (mut input, operand) = parse_and(input)?;
+    let mut operands = vec![operand];
+    while let Some(rest) = input.strip_prefix('|') {
+        let (rest, operand) = parse_and(rest)?;
+        input = rest;
+        operands.push(operand);
+    }
+
+    Ok((input, combine(operands, ConstraintExpression::Or)))
+}
+
+fn combine(
+    mut operands: Vec<ConstraintExpression>,
+    operator: fn(Vec<ConstraintExpression>) -> ConstraintExpression,
+) -> ConstraintExpression {
+    match operands.len() {
+        1 => operands.remove(0),
+        _ => operator(operands),
+    }
+}
+
 pub fn parse_status_constraints(
     input: &str,
-) -> Result<Vec<StatusConstraint>, ParseStatusConstraintsError<&str>> {
-    match many_till(parse_status_constraint, eof).map(|(constraints, _)| constraints).parse(input) {
-        Ok(("", constraints)) => Ok(constraints),
-        Ok((input, constraints)) => panic!(
-            "Failed to parse status constraints '{}': Unconsumed input '{}' after parsing contraints {:?}",
-            input, input, constraints
-        ),
-        Err(nom::Err::Failure(err)) | Err(nom::Err::Error(err)) => Err(err),
-        Err(err) => panic!("Failed to parse status constraints '{}': Unknown error: {:?}", input, err),
+) -> Result<ConstraintExpression, ParseStatusConstraintsError<&str>> {
+    if input.trim().is_empty() {
+        return Ok(ConstraintExpression::And(Vec::new()));
     }
+    let kind = match parse_or(input) {
+        Ok(("", expression)) => return Ok(expression),
+        Ok((rest, _)) => ParseErrorKind::UnexpectedToken {
+            allowed_tokens: "&|".to_string(),
+            name_allowed: false,
+            input: rest,
+        },
+        Err(nom::Err::Failure(kind)) | Err(nom::Err::Error(kind)) => kind,
+        Err(nom::Err::Incomplete(_)) => {
+            ParseErrorKind::UnknownError(&input[input.len()..], ErrorKind::Complete)
+        }
+    };
+    Err(ParseStatusConstraintsError { input, kind })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_status_constraints, ConstraintType, StatusConstraint};
+    use super::{
+        parse_status_constraints, ConstraintExpression, ConstraintType, ParseErrorKind,
+        ParseStatusConstraintsError, StatusConstraint,
+    };
+    use crate::signal::max_signal;
 
     // success cases
 
@@ -167,22 +574,20 @@ mod tests {
     fn positive_number() {
         assert_eq!(
             parse_status_constraints("42"),
-            Ok(vec![StatusConstraint {
-                typ: ConstraintType::Eq,
-                code: 42,
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Eq(42),
                 negated: false
-            }])
+            }))
         )
     }
     #[test]
     fn negative_number() {
         assert_eq!(
             parse_status_constraints("-42"),
-            Ok(vec![StatusConstraint {
-                typ: ConstraintType::Eq,
-                code: -42,
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Eq(-42),
                 negated: false
-            }])
+            }))
         )
     }
 
@@ -190,11 +595,10 @@ mod tests {
     fn negated() {
         assert_eq!(
             parse_status_constraints("!42"),
-            Ok(vec![StatusConstraint {
-                typ: ConstraintType::Eq,
-                code: 42,
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Eq(42),
                 negated: true
-            }])
+            }))
         )
     }
 
@@ -202,44 +606,40 @@ mod tests {
     fn explicit_equals() {
         assert_eq!(
             parse_status_constraints("=42"),
-            Ok(vec![StatusConstraint {
-                typ: ConstraintType::Eq,
-                code: 42,
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Eq(42),
                 negated: false
-            }])
+            }))
         )
     }
     #[test]
     fn greater_than() {
         assert_eq!(
             parse_status_constraints(">42"),
-            Ok(vec![StatusConstraint {
-                typ: ConstraintType::Gt,
-                code: 42,
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Gt(42),
                 negated: false
-            }])
+            }))
         )
     }
     #[test]
     fn less_than() {
         assert_eq!(
             parse_status_constraints("<42"),
-            Ok(vec![StatusConstraint {
-                typ: ConstraintType::Lt,
-                code: 42,
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Lt(42),
                 negated: false
-            }])
+            }))
         )
     }
     #[test]
     fn or_equal() {
         assert_eq!(
             parse_status_constraints(">=42"),
-            Ok(vec![StatusConstraint {
-                typ: ConstraintType::Gte,
-                code: 42,
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Gte(42),
                 negated: false
-            }])
+            }))
         )
     }
 
@@ -247,18 +647,321 @@ mod tests {
     fn multiple_constraints() {
         assert_eq!(
             parse_status_constraints("42 1337"),
-            Ok(vec![
-                StatusConstraint {
-                    typ: ConstraintType::Eq,
-                    code: 42,
+            Ok(ConstraintExpression::And(vec![
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::Eq(42),
                     negated: false
+                }),
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::Eq(1337),
+                    negated: false
+                })
+            ]))
+        )
+    }
+    #[test]
+    fn adjacent_constraints() {
+        assert_eq!(
+            parse_status_constraints(">42!100"),
+            parse_status_constraints(">42 & !100")
+        );
+        assert_eq!(
+            parse_status_constraints(">=1<=5"),
+            parse_status_constraints(">=1 & <=5")
+        );
+        assert_eq!(
+            parse_status_constraints("(1 | 2)3"),
+            parse_status_constraints("(1 | 2) & 3")
+        );
+    }
+
+    #[test]
+    fn signal_name() {
+        assert_eq!(
+            parse_status_constraints("INT"),
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Signal(2),
+                negated: false
+            }))
+        )
+    }
+    #[test]
+    fn prefixed_signal_name() {
+        assert_eq!(
+            parse_status_constraints("SIGINT"),
+            parse_status_constraints("INT")
+        )
+    }
+    #[test]
+    fn explicit_signal_name() {
+        assert_eq!(
+            parse_status_constraints("sig:TERM"),
+            parse_status_constraints("TERM")
+        )
+    }
+    #[test]
+    fn explicit_signal_number() {
+        assert_eq!(
+            parse_status_constraints("sig:9"),
+            parse_status_constraints("KILL")
+        )
+    }
+    #[test]
+    fn lowercase_signal_name() {
+        assert_eq!(
+            parse_status_constraints("sigterm"),
+            parse_status_constraints("TERM")
+        )
+    }
+    #[test]
+    fn negated_signal_name() {
+        assert_eq!(
+            parse_status_constraints("!SIGINT"),
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Signal(2),
+                negated: true
+            }))
+        )
+    }
+    #[test]
+    fn negation_after_operator() {
+        assert_eq!(
+            parse_status_constraints(">= !SIGINT").unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken {
+                allowed_tokens: "-0123456789".to_string(),
+                name_allowed: false,
+                input: "!SIGINT"
+            }
+        )
+    }
+    #[test]
+    fn any_signal() {
+        assert_eq!(
+            parse_status_constraints("signal !INT"),
+            Ok(ConstraintExpression::And(vec![
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::Signaled,
+                    negated: false
+                }),
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::Signal(2),
+                    negated: true
+                })
+            ]))
+        )
+    }
+
+    #[test]
+    fn keywords() {
+        assert_eq!(
+            parse_status_constraints("exited !COREDUMP | timeout | spawn-failed"),
+            Ok(ConstraintExpression::Or(vec![
+                ConstraintExpression::And(vec![
+                    ConstraintExpression::Constraint(StatusConstraint {
+                        typ: ConstraintType::Exited,
+                        negated: false
+                    }),
+                    ConstraintExpression::Constraint(StatusConstraint {
+                        typ: ConstraintType::CoreDumped,
+                        negated: true
+                    })
+                ]),
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::TimedOut,
+                    negated: false
+                }),
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::SpawnFailed,
+                    negated: false
+                })
+            ]))
+        )
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(
+            parse_status_constraints("success"),
+            parse_status_constraints("(exited & 0)")
+        );
+        assert_eq!(
+            parse_status_constraints("Failure"),
+            parse_status_constraints("!(exited & 0)")
+        );
+        assert_eq!(
+            parse_status_constraints("!abort 1"),
+            parse_status_constraints("!(signal !HUP !INT !TERM !PIPE) 1")
+        )
+    }
+
+    #[test]
+    fn bits_set() {
+        assert_eq!(
+            parse_status_constraints("!&4"),
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::BitsSet(4),
+                negated: true
+            }))
+        )
+    }
+    #[test]
+    fn multiple_bits_set() {
+        assert_eq!(
+            parse_status_constraints("&1&&4"),
+            parse_status_constraints("&1 & &4")
+        );
+        assert_ne!(
+            parse_status_constraints("1 & 4"),
+            parse_status_constraints("1 & &4")
+        )
+    }
+
+    #[test]
+    fn constraint_set() {
+        assert_eq!(
+            parse_status_constraints("!@ssh-transient | 1"),
+            parse_status_constraints("!(255) | 1")
+        )
+    }
+
+    #[test]
+    fn inclusive_range() {
+        assert_eq!(
+            parse_status_constraints("1..=10"),
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Range {
+                    start: 1,
+                    end: 10,
+                    inclusive: true
+                },
+                negated: false
+            }))
+        )
+    }
+    #[test]
+    fn exclusive_range() {
+        assert_eq!(
+            parse_status_constraints("!-1..10"),
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Range {
+                    start: -1,
+                    end: 10,
+                    inclusive: false
                 },
-                StatusConstraint {
-                    typ: ConstraintType::Eq,
-                    code: 1337,
+                negated: true
+            }))
+        )
+    }
+    #[test]
+    fn list() {
+        assert_eq!(
+            parse_status_constraints("1,2, 75"),
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::List(vec![1, 2, 75]),
+                negated: false
+            }))
+        )
+    }
+
+    #[test]
+    fn explicit_and() {
+        assert_eq!(
+            parse_status_constraints(">=1&<=5"),
+            parse_status_constraints(">=1 <=5")
+        )
+    }
+    #[test]
+    fn or() {
+        assert_eq!(
+            parse_status_constraints("1 | 2"),
+            Ok(ConstraintExpression::Or(vec![
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::Eq(1),
                     negated: false
-                }
-            ])
+                }),
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::Eq(2),
+                    negated: false
+                })
+            ]))
+        )
+    }
+    #[test]
+    fn and_binds_stronger_than_or() {
+        assert_eq!(
+            parse_status_constraints("1 | >=2 & <=5"),
+            Ok(ConstraintExpression::Or(vec![
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::Eq(1),
+                    negated: false
+                }),
+                ConstraintExpression::And(vec![
+                    ConstraintExpression::Constraint(StatusConstraint {
+                        typ: ConstraintType::Gte(2),
+                        negated: false
+                    }),
+                    ConstraintExpression::Constraint(StatusConstraint {
+                        typ: ConstraintType::Lte(5),
+                        negated: false
+                    })
+                ])
+            ]))
+        )
+    }
+    #[test]
+    fn parentheses() {
+        assert_eq!(
+            parse_status_constraints("(>=1 & <=5) | SIGTERM"),
+            parse_status_constraints(">=1 <=5 | TERM")
+        );
+        assert_eq!(
+            parse_status_constraints("((1|2)) 3"),
+            Ok(ConstraintExpression::And(vec![
+                ConstraintExpression::Or(vec![
+                    ConstraintExpression::Constraint(StatusConstraint {
+                        typ: ConstraintType::Eq(1),
+                        negated: false
+                    }),
+                    ConstraintExpression::Constraint(StatusConstraint {
+                        typ: ConstraintType::Eq(2),
+                        negated: false
+                    })
+                ]),
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::Eq(3),
+                    negated: false
+                })
+            ]))
+        )
+    }
+    #[test]
+    fn negated_parentheses() {
+        assert_eq!(
+            parse_status_constraints("! (1)"),
+            Ok(ConstraintExpression::Not(Box::new(
+                ConstraintExpression::Constraint(StatusConstraint {
+                    typ: ConstraintType::Eq(1),
+                    negated: false
+                })
+            )))
+        )
+    }
+    #[test]
+    fn repeated_negation() {
+        assert_eq!(
+            parse_status_constraints("!!1"),
+            parse_status_constraints("!(!1)")
+        );
+        assert_eq!(
+            parse_status_constraints("! ! !(1)"),
+            parse_status_constraints("!(!(!(1)))")
+        )
+    }
+    #[test]
+    fn empty() {
+        assert_eq!(
+            parse_status_constraints(" "),
+            Ok(ConstraintExpression::And(vec![]))
         )
     }
 
@@ -266,11 +969,10 @@ mod tests {
     fn whitespace() {
         assert_eq!(
             parse_status_constraints(" ! >= -42 "),
-            Ok(vec![StatusConstraint {
-                typ: ConstraintType::Gte,
-                code: -42,
+            Ok(ConstraintExpression::Constraint(StatusConstraint {
+                typ: ConstraintType::Gte(-42),
                 negated: true
-            }])
+            }))
         )
     }
 
@@ -278,7 +980,7 @@ mod tests {
 
     #[test]
     fn integer_out_of_bounds_error() {
-        let input = i64::max_value().to_string();
+        let input = i64::MAX.to_string();
         let err = parse_status_constraints(&input).unwrap_err();
         assert!(err.to_string().contains(&input));
         assert!(err.to_string().to_lowercase().contains("too high"));
@@ -286,26 +988,298 @@ mod tests {
 
     #[test]
     fn invalid_token() {
-        insta::assert_display_snapshot!(parse_status_constraints("x").unwrap_err(), @"Expected one of '!><=-0123456789', got: x");
+        insta::assert_snapshot!(parse_status_constraints("#").unwrap_err().to_string(), @r###"
+            Expected one of '!><&=(@-0123456789' or a name, got: #
+              #
+              ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
     }
     #[test]
     fn invalid_token_after_negation() {
-        insta::assert_display_snapshot!(parse_status_constraints("!x").unwrap_err(), @"Expected one of '><=-0123456789', got: x");
+        insta::assert_snapshot!(parse_status_constraints("!#").unwrap_err().to_string(), @r###"
+            Expected one of '><&=(@-0123456789' or a name, got: #
+              !#
+               ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
     }
     #[test]
     fn invalid_token_after_operator() {
-        insta::assert_display_snapshot!(parse_status_constraints(">x").unwrap_err(), @"Expected one of '=-0123456789', got: x");
+        insta::assert_snapshot!(parse_status_constraints(">#").unwrap_err().to_string(), @r###"
+            Expected one of '=-0123456789', got: #
+              >#
+               ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
     }
     #[test]
     fn invalid_token_after_equal_sign() {
-        insta::assert_display_snapshot!(parse_status_constraints("=x").unwrap_err(), @"Expected one of '-0123456789', got: x");
+        insta::assert_snapshot!(parse_status_constraints("=#").unwrap_err().to_string(), @r###"
+            Expected one of '-0123456789', got: #
+              =#
+               ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
     }
     #[test]
     fn invalid_token_after_minus() {
-        insta::assert_display_snapshot!(parse_status_constraints("-x").unwrap_err(), @"Expected one of '0123456789', got: x");
+        insta::assert_snapshot!(parse_status_constraints("-x").unwrap_err().to_string(), @r###"
+            Expected one of '0123456789', got: x
+              -x
+               ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
     }
     #[test]
     fn invalid_token_after_number() {
-        insta::assert_display_snapshot!(parse_status_constraints("4x").unwrap_err(), @"Expected one of '!><=-0123456789', got: x");
+        insta::assert_snapshot!(parse_status_constraints("4#").unwrap_err().to_string(), @r###"
+            Expected one of '&|', got: #
+              4#
+               ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn unknown_signal_name() {
+        insta::assert_snapshot!(parse_status_constraints("SIGFOO").unwrap_err().to_string(), @r###"
+            Unknown signal name 'SIGFOO'
+              SIGFOO
+              ^^^^^^
+            Hint: Signal names look like 'TERM', 'SIGTERM' or 'sig:15'. Keywords are 'exited', 'signal', 'coredump', 'timeout' and 'spawn-failed', aliases are 'success', 'failure' and 'abort'.
+            "###);
+    }
+    #[test]
+    fn unknown_explicit_signal_name() {
+        insta::assert_snapshot!(parse_status_constraints("sig:FOO").unwrap_err().to_string(), @r###"
+            Unknown signal name 'FOO'
+              sig:FOO
+                  ^^^
+            Hint: Signal names look like 'TERM', 'SIGTERM' or 'sig:15'. Keywords are 'exited', 'signal', 'coredump', 'timeout' and 'spawn-failed', aliases are 'success', 'failure' and 'abort'.
+            "###);
+    }
+    #[test]
+    fn any_signal_with_operator() {
+        insta::assert_snapshot!(parse_status_constraints(">signal").unwrap_err().to_string(), @r###"
+            Expected one of '=-0123456789', got: signal
+              >signal
+               ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn invalid_token_in_range() {
+        insta::assert_snapshot!(parse_status_constraints("1..#").unwrap_err().to_string(), @r###"
+            Expected one of '=-0123456789', got: #
+              1..#
+                 ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn invalid_token_in_list() {
+        insta::assert_snapshot!(parse_status_constraints("1,#").unwrap_err().to_string(), @r###"
+            Expected one of '-0123456789', got: #
+              1,#
+                ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn any_signal_in_list() {
+        insta::assert_snapshot!(parse_status_constraints("1,signal").unwrap_err().to_string(), @r###"
+            Expected one of '-0123456789', got: signal
+              1,signal
+                ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn unclosed_parenthesis() {
+        insta::assert_snapshot!(parse_status_constraints("(1 | 2").unwrap_err().to_string(), @r###"
+            Expected one of '&|)', got end of input
+              (1 | 2
+                    ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn unopened_parenthesis() {
+        insta::assert_snapshot!(parse_status_constraints("1 | 2)").unwrap_err().to_string(), @r###"
+            Expected one of '&|', got: )
+              1 | 2)
+                   ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn missing_operand() {
+        insta::assert_snapshot!(parse_status_constraints("1 |").unwrap_err().to_string(), @r###"
+            Expected one of '!><&=(@-0123456789' or a name, got end of input
+              1 |
+                 ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn explicit_signal_keyword() {
+        insta::assert_snapshot!(parse_status_constraints("sig:timeout").unwrap_err().to_string(), @r###"
+            Unknown signal name 'timeout'
+              sig:timeout
+                  ^^^^^^^
+            Hint: Signal names look like 'TERM', 'SIGTERM' or 'sig:15'. Keywords are 'exited', 'signal', 'coredump', 'timeout' and 'spawn-failed', aliases are 'success', 'failure' and 'abort'.
+            "###);
+    }
+    #[test]
+    fn alias_in_list() {
+        insta::assert_snapshot!(parse_status_constraints("1,success").unwrap_err().to_string(), @r###"
+            Expected one of '-0123456789', got: success
+              1,success
+                ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn invalid_token_after_bit_test() {
+        insta::assert_snapshot!(parse_status_constraints("&=4").unwrap_err().to_string(), @r###"
+            Expected one of '-0123456789', got: =4
+              &=4
+               ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn unknown_constraint_set() {
+        insta::assert_snapshot!(parse_status_constraints("@foo").unwrap_err().to_string(), @r###"
+            Unknown constraint set '@foo'
+              @foo
+               ^^^
+            Hint: Built-in sets are '@curl-transient', '@wget-transient', '@rsync-partial', '@rsync-transient', '@ssh-transient', '@git-transient'. More sets can be defined in the file at $PRCS_CONSTRAINT_SETS or $XDG_CONFIG_HOME/prcs/constraint-sets.
+            "###);
+    }
+    #[test]
+    fn too_high_status_code() {
+        insta::assert_snapshot!(parse_status_constraints("1 | >99999999999").unwrap_err().to_string(), @r###"
+            Failed to parse '99999999999' as a status code. Integer too high.
+              1 | >99999999999
+                   ^^^^^^^^^^^
+            Hint: Status codes range from -2147483648 to 2147483647.
+            "###);
+    }
+    #[test]
+    fn error_in_later_operand() {
+        insta::assert_snapshot!(parse_status_constraints("(TERM | ÄRGER) & !0").unwrap_err().to_string(), @r###"
+            Expected one of '!><&=(@-0123456789' or a name, got: ÄRGER) & !0
+              (TERM | ÄRGER) & !0
+                      ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn error_in_constraint_set() {
+        insta::assert_snapshot!(ParseStatusConstraintsError {
+            input: "@foo",
+            kind: ParseErrorKind::InSet("foo", Box::new(ParseErrorKind::UnknownSignal("BAR"))),
+        }.to_string(), @r###"
+            In constraint set '@foo': Unknown signal name 'BAR'
+            Hint: Signal names look like 'TERM', 'SIGTERM' or 'sig:15'. Keywords are 'exited', 'signal', 'coredump', 'timeout' and 'spawn-failed', aliases are 'success', 'failure' and 'abort'.
+            "###);
+    }
+    #[test]
+    fn invalid_token_after_range() {
+        insta::assert_snapshot!(parse_status_constraints("1..=2..=3").unwrap_err().to_string(), @r###"
+            Expected one of '&|', got: ..=3
+              1..=2..=3
+                   ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn ambiguous_bit_test() {
+        insta::assert_snapshot!(parse_status_constraints("1&4").unwrap_err().to_string(), @r###"
+            Ambiguous bit test '&4' after another constraint
+              1&4
+               ^^
+            Hint: After a constraint, '&' combines it with the next one, like '1 & 4' for status 1 and 4. Use '1 & &4' to test the bits of the mask 4 instead.
+            "###);
+    }
+    #[test]
+    fn ambiguous_bit_test_after_whitespace() {
+        insta::assert_snapshot!(parse_status_constraints("!0 &-128").unwrap_err().to_string(), @r###"
+            Ambiguous bit test '&-128' after another constraint
+              !0 &-128
+                 ^^^^^
+            Hint: After a constraint, '&' combines it with the next one, like '1 & 4' for status 1 and 4. Use '1 & &4' to test the bits of the mask 4 instead.
+            "###);
+    }
+    #[test]
+    fn empty_parentheses() {
+        insta::assert_snapshot!(parse_status_constraints("()").unwrap_err().to_string(), @r###"
+            Expected one of '!><&=(@-0123456789' or a name, got: )
+              ()
+               ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn only_opening_parenthesis() {
+        insta::assert_snapshot!(parse_status_constraints("(").unwrap_err().to_string(), @r###"
+            Expected one of '!><&=(@-0123456789' or a name, got end of input
+              (
+               ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn invalid_token_after_parenthesis() {
+        insta::assert_snapshot!(parse_status_constraints("(1 | 2),3").unwrap_err().to_string(), @r###"
+            Expected one of '&|', got: ,3
+              (1 | 2),3
+                     ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn too_high_signal_number() {
+        insta::assert_snapshot!(parse_status_constraints("exited | sig:2147483647").unwrap_err().to_string().replace(&format!("to {}", max_signal()), "to <max>"), @r###"
+            Signal number 2147483647 is out of range, expected 1 to <max>
+              exited | sig:2147483647
+                           ^^^^^^^^^^
+            "###);
+    }
+    #[test]
+    fn unknown_signal_number() {
+        insta::assert_snapshot!(parse_status_constraints("sig:99").unwrap_err().to_string().replace(&format!("to {}", max_signal()), "to <max>"), @r###"
+            Signal number 99 is out of range, expected 1 to <max>
+              sig:99
+                  ^^
+            "###);
+    }
+    #[test]
+    fn signal_name_with_operator() {
+        insta::assert_snapshot!(parse_status_constraints("!>=SIGINT").unwrap_err().to_string(), @r###"
+            Expected one of '-0123456789', got: SIGINT
+              !>=SIGINT
+                 ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn signal_name_in_range() {
+        insta::assert_snapshot!(parse_status_constraints("HUP..=TERM").unwrap_err().to_string(), @r###"
+            Expected one of '&|', got: ..=TERM
+              HUP..=TERM
+                 ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
+    }
+    #[test]
+    fn signal_name_in_list() {
+        insta::assert_snapshot!(parse_status_constraints("1,INT").unwrap_err().to_string(), @r###"
+            Expected one of '-0123456789', got: INT
+              1,INT
+                ^
+            Hint: Constraints look like '0', '!0', '>=128', '1..=10', '1,2,75', '&4', 'SIGTERM', 'exited', 'success' or '@curl-transient', and can be combined with '&', '|', '!' and parentheses.
+            "###);
     }
 }
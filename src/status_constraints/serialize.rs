@@ -0,0 +1,75 @@
+use super::{parse_status_constraints, ConstraintExpression, StatusConstraint};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+// constraints are (de)serialized in the same syntax as on the command line
+
+impl Serialize for ConstraintExpression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+impl<'de> Deserialize<'de> for ConstraintExpression {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        parse_status_constraints(&input).map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for StatusConstraint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+impl<'de> Deserialize<'de> for StatusConstraint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        match parse_status_constraints(&input).map_err(D::Error::custom)? {
+            ConstraintExpression::Constraint(constraint) => Ok(constraint),
+            _ => Err(D::Error::custom(format!(
+                "Expected a single status constraint, got: {}",
+                input
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_status_constraints, ConstraintType};
+    use super::{ConstraintExpression, StatusConstraint};
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+    #[test]
+    fn expression() {
+        assert_tokens(
+            &parse_status_constraints("1..=10 & !SIGINT | timeout").unwrap(),
+            &[Token::Str("1..=10 & !SIGINT | timeout")],
+        );
+    }
+    #[test]
+    fn constraint() {
+        assert_tokens(
+            &StatusConstraint {
+                typ: ConstraintType::Gte(128),
+                negated: true,
+            },
+            &[Token::Str("!>=128")],
+        );
+    }
+
+    #[test]
+    fn invalid_expression() {
+        assert_de_tokens_error::<ConstraintExpression>(
+            &[Token::Str("SIGFOO")],
+            "Unknown signal name 'SIGFOO'\n  SIGFOO\n  ^^^^^^\nHint: Signal names look like 'TERM', 'SIGTERM' or 'sig:15'. \
+            Keywords are 'exited', 'signal', 'coredump', 'timeout' and 'spawn-failed', aliases are 'success', 'failure' and 'abort'.",
+        );
+    }
+    #[test]
+    fn multiple_constraints() {
+        assert_de_tokens_error::<StatusConstraint>(
+            &[Token::Str("1 | 2")],
+            "Expected a single status constraint, got: 1 | 2",
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Constraint sets shipped with prcs, mostly exit codes of well-known tools that indicate a transient failure.
+pub const BUILTIN_SETS: &[(&str, &str)] = &[
+    // couldn't resolve proxy/host, failed to connect, HTTP/2 error, partial file, timeout, SSL connect error,
+    // empty reply, send/receive error, HTTP/2 stream error
+    ("curl-transient", "5,6,7,16,18,28,35,52,55,56,92"),
+    // network failure
+    ("wget-transient", "4"),
+    // partial transfer due to error or vanished source files
+    ("rsync-partial", "23,24"),
+    // socket I/O error, protocol data stream error, timeouts
+    ("rsync-transient", "10,12,30,35"),
+    // connection error
+    ("ssh-transient", "255"),
+    // fatal error, which includes network failures
+    ("git-transient", "128"),
+];
+
+/// Environment variable to override the path of the file with user-defined constraint sets.
+const PATH_VARIABLE: &str = "PRCS_CONSTRAINT_SETS";
+
+fn path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(PATH_VARIABLE) {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(config_dir.join("prcs").join("constraint-sets"))
+}
+
+/// Parses constraint sets given as lines of `<name> = <constraints>`, ignoring empty lines and `#` comments.
+fn parse_sets(content: &str) -> Result<Vec<(String, String)>, String> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| match line.split_once('=') {
+            Some((name, constraints)) if !name.trim().is_empty() => Ok((
+                name.trim().trim_start_matches('@').to_string(),
+                constraints.trim().to_string(),
+            )),
+            _ => Err(format!(
+                "Expected '<name> = <constraints>' in line {}, got: {}",
+                index + 1,
+                line
+            )),
+        })
+        .collect()
+}
+
+/// Constraint sets defined by the user, read once from the file at [`path`].
+fn user_sets() -> &'static Result<Vec<(String, String)>, String> {
+    static USER_SETS: OnceLock<Result<Vec<(String, String)>, String>> = OnceLock::new();
+    USER_SETS.get_or_init(|| {
+        let path = match path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Vec::new()),
+        };
+        std::fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|content| parse_sets(&content))
+            .map_err(|err| {
+                format!(
+                    "Failed to read constraint sets from '{}': {}",
+                    path.display(),
+                    err
+                )
+            })
+    })
+}
+
+/// Looks up the constraints of a set, preferring user-defined sets over built-in ones.
+pub fn lookup(name: &str) -> Result<Option<&'static str>, &'static str> {
+    let user_sets = user_sets().as_ref().map_err(String::as_str)?;
+    Ok(user_sets
+        .iter()
+        .map(|(name, constraints)| (name.as_str(), constraints.as_str()))
+        .chain(BUILTIN_SETS.iter().copied())
+        .find(|(known_name, _)| *known_name == name)
+        .map(|(_, constraints)| constraints))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_sets, BUILTIN_SETS};
+    use crate::status_constraints::parse_status_constraints;
+
+    #[test]
+    fn builtin_sets() {
+        for (_, constraints) in BUILTIN_SETS {
+            assert!(parse_status_constraints(constraints).is_ok());
+        }
+    }
+
+    #[test]
+    fn sets() {
+        assert_eq!(
+            parse_sets("# comment\n\nmine = 1 | 2\n @other=>=3 "),
+            Ok(vec![
+                ("mine".to_string(), "1 | 2".to_string()),
+                ("other".to_string(), ">=3".to_string())
+            ])
+        )
+    }
+    #[test]
+    fn invalid_line() {
+        insta::assert_snapshot!(parse_sets("mine = 1\nmine").unwrap_err(), @"Expected '<name> = <constraints>' in line 2, got: mine");
+    }
+}
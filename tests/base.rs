@@ -11,7 +11,7 @@ use utils::shell_command;
 #[test]
 fn forwards_status_code() {
     let status_code = Command::new(env!("CARGO_BIN_EXE_prcs"))
-        .args(&shell_command("exit 42"))
+        .args(shell_command("exit 42"))
         .status()
         .unwrap()
         .code()
@@ -22,7 +22,7 @@ fn forwards_status_code() {
 #[test]
 fn forwards_stdout() {
     let stdout = Command::new(env!("CARGO_BIN_EXE_prcs"))
-        .args(&shell_command("echo text"))
+        .args(shell_command("echo text"))
         .output()
         .unwrap()
         .stdout;
@@ -35,7 +35,7 @@ fn forwards_stdout() {
 #[test]
 fn forwards_stderr() {
     let stderr = Command::new(env!("CARGO_BIN_EXE_prcs"))
-        .args(&shell_command("echo text 1>&2"))
+        .args(shell_command("echo text 1>&2"))
         .output()
         .unwrap()
         .stderr;
@@ -52,7 +52,7 @@ fn forwards_stdin() {
     tmp_file_path.push("file.txt");
 
     let mut child = Command::new(env!("CARGO_BIN_EXE_prcs"))
-        .args(&shell_command(if cfg!(windows) {
+        .args(shell_command(if cfg!(windows) {
             "more >%TMP_FILE_PATH%"
         } else {
             "cat >$TMP_FILE_PATH"
@@ -0,0 +1,38 @@
+#![cfg(target_os = "linux")]
+
+mod utils;
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::thread::sleep;
+use std::time::Duration;
+use utils::{exists, parent, shell_command};
+
+#[test]
+fn reaps_orphans() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--init")
+        .args(shell_command(
+            "(sleep 0.5 >/dev/null & echo $!); sleep 1.5; exit 42",
+        ))
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut pid = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut pid)
+        .unwrap();
+
+    let pid = pid.trim().parse().unwrap();
+
+    // the orphan is only reaped by prcs if it becomes its parent once the subshell exits
+    sleep(Duration::from_millis(200));
+    let orphan_parent = parent(pid);
+    sleep(Duration::from_millis(700));
+    let orphan_exists = exists(pid);
+    let status = child.wait().unwrap();
+
+    assert_eq!(orphan_parent, Some(child.id()));
+    assert!(!orphan_exists);
+    assert_eq!(status.code().unwrap(), 42)
+}
@@ -0,0 +1,48 @@
+#![cfg(target_os = "linux")]
+
+mod utils;
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::thread::sleep;
+use std::time::Duration;
+use utils::{running, shell_command};
+
+/// Runs a process under prcs with the given arguments, kills prcs, and returns the process ID of the process.
+fn kill_prcs(args: &[&str]) -> u32 {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .args(args)
+        .args(shell_command("echo $$; exec sleep 30"))
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut pid = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut pid)
+        .unwrap();
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    sleep(Duration::from_millis(100));
+    pid.trim().parse().unwrap()
+}
+
+#[test]
+fn kills_process_when_prcs_dies() {
+    let pid = kill_prcs(&[]);
+    assert!(!running(pid));
+}
+
+#[test]
+fn sends_configured_signal_when_prcs_dies() {
+    let pid = kill_prcs(&["--parent-death-signal=TERM"]);
+    assert!(!running(pid));
+}
+
+#[test]
+fn keeps_process_running_if_disabled() {
+    let pid = kill_prcs(&["--parent-death-signal=0"]);
+    let still_running = running(pid);
+    Command::new("kill").arg(pid.to_string()).status().unwrap();
+    assert!(still_running);
+}
@@ -0,0 +1,53 @@
+#![cfg(unix)]
+
+mod utils;
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::thread::sleep;
+use std::time::Duration;
+use utils::{running, shell_command};
+
+fn run_and_read_background_pid(command: &mut Command) -> (u32, Option<i32>) {
+    let mut child = command.stdout(Stdio::piped()).spawn().unwrap();
+    let mut pid = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut pid)
+        .unwrap();
+    let status = child.wait().unwrap();
+    (pid.trim().parse().unwrap(), status.code())
+}
+
+#[test]
+fn kills_descendants_after_process_exited() {
+    let (pid, code) = run_and_read_background_pid(
+        Command::new(env!("CARGO_BIN_EXE_prcs"))
+            .arg("--process-group")
+            .args(shell_command("sleep 30 & echo $!")),
+    );
+    assert_eq!(code, Some(0));
+    sleep(Duration::from_millis(100));
+    assert!(!running(pid))
+}
+
+#[test]
+fn signals_descendants_on_timeout() {
+    let (pid, code) = run_and_read_background_pid(
+        Command::new(env!("CARGO_BIN_EXE_prcs"))
+            .arg("--process-group")
+            .arg("--timeout=100ms")
+            .args(shell_command("sleep 30 & echo $!; wait")),
+    );
+    assert_eq!(code, Some(124));
+    sleep(Duration::from_millis(100));
+    assert!(!running(pid))
+}
+
+#[test]
+fn leaves_descendants_without_process_group() {
+    let (pid, code) = run_and_read_background_pid(
+        Command::new(env!("CARGO_BIN_EXE_prcs")).args(shell_command("sleep 1 & echo $!")),
+    );
+    assert_eq!(code, Some(0));
+    assert!(running(pid))
+}
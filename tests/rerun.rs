@@ -7,14 +7,14 @@ use std::process::Child;
 use std::process::{Command, Stdio};
 use std::str::from_utf8;
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use utils::shell_command;
 
 #[test]
 fn does_not_rerun_if_status_does_not_match() {
     let stdout = Command::new(env!("CARGO_BIN_EXE_prcs"))
         .arg("-r=100")
-        .args(&shell_command("echo text"))
+        .args(shell_command("echo text"))
         .output()
         .unwrap()
         .stdout;
@@ -24,7 +24,7 @@ fn does_not_rerun_if_status_does_not_match() {
     )
 }
 
-const EXPECTED_OUTPUT: &'static str = if cfg!(windows) {
+const EXPECTED_OUTPUT: &str = if cfg!(windows) {
     "text\r\nEND\r\n"
 } else {
     "text\nEND\n"
@@ -40,7 +40,7 @@ fn run_consume_and_print_one_stdin_line(command: &mut Command) -> Child {
                 .unwrap(),
         ]);
     } else {
-        command.args(&shell_command(
+        command.args(shell_command(
             "(read line && echo $line) || (echo END && false)",
         ));
     }
@@ -101,5 +101,380 @@ fn reruns_before_stalling() {
     assert_eq!(from_utf8(&output).unwrap(), EXPECTED_OUTPUT);
 
     sleep(Duration::from_millis(100));
-    assert!(child.try_wait().unwrap().is_none())
+    assert!(child.try_wait().unwrap().is_none());
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn stops_rerunning_after_max_tries() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("--max-tries=3")
+        .args(shell_command("echo text && exit 42"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 42);
+    assert_eq!(
+        from_utf8(&output.stdout).unwrap(),
+        if cfg!(windows) {
+            "text \r\ntext \r\ntext \r\n"
+        } else {
+            "text\ntext\ntext\n"
+        }
+    )
+}
+
+#[test]
+fn max_tries_of_one_does_not_rerun() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("--max-tries=1")
+        .args(shell_command("echo text && exit 42"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 42);
+    assert_eq!(
+        from_utf8(&output.stdout).unwrap(),
+        if cfg!(windows) { "text \r\n" } else { "text\n" }
+    )
+}
+
+#[test]
+fn waits_between_reruns() {
+    let start = Instant::now();
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("--max-tries=3")
+        .arg("--rerun-delay=100ms+100ms")
+        .args(shell_command("exit 42"))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 42);
+    assert!(start.elapsed() >= Duration::from_millis(300))
+}
+
+#[test]
+fn stops_rerunning_after_rerun_for() {
+    let start = Instant::now();
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("--rerun-for=300ms")
+        .arg("--rerun-delay=100ms")
+        .args(shell_command("exit 42"))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 42);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert!(start.elapsed() < Duration::from_secs(10))
+}
+
+#[test]
+fn gives_up_after_exceeding_rerun_limit() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("--rerun-limit=2/1h")
+        .args(shell_command("echo text && exit 42"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 125);
+    assert_eq!(
+        from_utf8(&output.stdout).unwrap(),
+        if cfg!(windows) {
+            "text \r\ntext \r\ntext \r\n"
+        } else {
+            "text\ntext\ntext\n"
+        }
+    );
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .to_lowercase()
+        .contains("rerun limit"))
+}
+
+#[test]
+fn cools_down_after_exceeding_rerun_limit() {
+    let start = Instant::now();
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("--max-tries=3")
+        .arg("--rerun-limit=1/1h")
+        .arg("--rerun-limit-cooldown=200ms")
+        .args(shell_command("exit 42"))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 42);
+    assert!(start.elapsed() >= Duration::from_millis(200))
+}
+
+#[cfg(unix)]
+#[test]
+fn starts_over_after_stable_run() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("--max-tries=2")
+        .arg("--rerun-stable-after=200ms")
+        .args(shell_command(
+            "if read line; then sleep 0.3 && echo $line; else echo END; fi; exit 42",
+        ))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"1\n2\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(output.status.code().unwrap(), 42);
+    assert_eq!(from_utf8(&output.stdout).unwrap(), "1\n2\nEND\n")
+}
+
+#[test]
+fn applies_tries_per_option() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r=0; tries=3")
+        .arg("-r=42; tries=1")
+        .args(shell_command("echo text"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 0);
+    assert_eq!(
+        from_utf8(&output.stdout).unwrap(),
+        if cfg!(windows) {
+            "text\r\ntext\r\ntext\r\n"
+        } else {
+            "text\ntext\ntext\n"
+        }
+    )
+}
+
+#[test]
+fn applies_delay_per_option() {
+    let start = Instant::now();
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r=42; tries=3; delay=100ms")
+        .arg("--rerun-delay=1h")
+        .args(shell_command("exit 42"))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 42);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert!(start.elapsed() < Duration::from_secs(60))
+}
+
+#[test]
+fn exposes_attempt_to_process() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("--max-tries=2")
+        .args(shell_command(if cfg!(windows) {
+            "echo %PRCS_ATTEMPT%/%PRCS_MAX_TRIES%&& exit 42"
+        } else {
+            "echo $PRCS_ATTEMPT/$PRCS_MAX_TRIES && exit 42"
+        }))
+        .output()
+        .unwrap();
+    assert_eq!(
+        from_utf8(&output.stdout).unwrap(),
+        if cfg!(windows) {
+            "1/2\r\n2/2\r\n"
+        } else {
+            "1/2\n2/2\n"
+        }
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn exposes_previous_status_and_elapsed_time_to_process() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("--max-tries=2")
+        .arg("--rerun-delay=100ms")
+        .args(shell_command(
+            "echo ${PRCS_PREVIOUS_STATUS-none} $PRCS_ELAPSED_MS && exit 42",
+        ))
+        .output()
+        .unwrap();
+    let stdout = from_utf8(&output.stdout).unwrap();
+    let lines: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split(' ').collect())
+        .collect();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0][0], "none");
+    assert!(lines[0][1].parse::<u64>().unwrap() < 100);
+    assert_eq!(lines[1][0], "42");
+    assert!(lines[1][1].parse::<u64>().unwrap() >= 100);
+}
+
+#[test]
+fn exits_with_first_status() {
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("--max-tries=3")
+        .arg("--exit-status=first")
+        .args(shell_command(if cfg!(windows) {
+            "exit %PRCS_ATTEMPT%"
+        } else {
+            "exit $PRCS_ATTEMPT"
+        }))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 1)
+}
+
+#[test]
+fn exits_with_worst_status() {
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r=>=0")
+        .arg("--max-tries=3")
+        .arg("--exit-status=worst")
+        .args(shell_command(if cfg!(windows) {
+            "exit %PRCS_ATTEMPT%"
+        } else {
+            "exit $PRCS_ATTEMPT"
+        }))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 3)
+}
+
+#[test]
+fn reruns_if_expression_matches() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r=(>=1 & <=5) | 75")
+        .arg("--max-tries=2")
+        .args(shell_command("echo run&& exit 75"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 75);
+    assert_eq!(from_utf8(&output.stdout).unwrap().lines().count(), 2)
+}
+
+#[cfg(unix)]
+#[test]
+fn distinguishes_signals_from_exit_statuses() {
+    let run = |constraint: &str, command: &'static str| {
+        Command::new(env!("CARGO_BIN_EXE_prcs"))
+            .arg(format!("-r={}", constraint))
+            .arg("--max-tries=2")
+            .args(shell_command(command))
+            .output()
+            .unwrap()
+            .stdout
+            .len()
+    };
+    assert_eq!(run("exited & 130", "echo run; exit 130"), 8);
+    assert_eq!(run("exited & 130", "echo run; kill -INT $$"), 4);
+    assert_eq!(run("signal & INT", "echo run; kill -INT $$"), 8);
+    assert_eq!(run("signal & INT", "echo run; exit 130"), 4);
+    assert_eq!(run("INT", "echo run; kill -INT $$"), 8);
+    assert_eq!(run("INT", "echo run; exit 130"), 4);
+}
+
+#[test]
+fn reruns_if_spawn_failed() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r=spawn-failed")
+        .arg("--max-tries=2")
+        .arg("executable-that-almost-certainly-does-not-exist")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 127);
+    assert_eq!(
+        from_utf8(&output.stderr)
+            .unwrap()
+            .matches("Failed to run")
+            .count(),
+        2
+    )
+}
+
+#[test]
+fn does_not_rerun_if_spawn_failed_by_default() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r")
+        .arg("executable-that-almost-certainly-does-not-exist")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 127);
+    assert_eq!(
+        from_utf8(&output.stderr)
+            .unwrap()
+            .matches("Failed to run")
+            .count(),
+        1
+    )
+}
+
+#[test]
+fn does_not_rerun_if_spawn_failed_on_failure() {
+    for constraints in ["-r=failure", "-r=!(0)"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+            .arg(constraints)
+            .arg("executable-that-almost-certainly-does-not-exist")
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code().unwrap(), 127);
+        assert_eq!(
+            from_utf8(&output.stderr)
+                .unwrap()
+                .matches("Failed to run")
+                .count(),
+            1,
+            "{}",
+            constraints
+        )
+    }
+}
+
+#[test]
+fn restarts_according_to_preset() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--restart=on-failure")
+        .arg("--max-tries=3")
+        .args(shell_command("echo run&& exit 1"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 1);
+    assert_eq!(from_utf8(&output.stdout).unwrap().lines().count(), 3);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--restart=on-abnormal")
+        .arg("--max-tries=3")
+        .args(shell_command("echo run&& exit 1"))
+        .output()
+        .unwrap();
+    assert_eq!(from_utf8(&output.stdout).unwrap().lines().count(), 1);
+}
+
+#[test]
+fn reruns_if_bits_set() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r=&4 !&1")
+        .arg("--max-tries=2")
+        .args(shell_command("echo run&& exit 6"))
+        .output()
+        .unwrap();
+    assert_eq!(from_utf8(&output.stdout).unwrap().lines().count(), 2);
+}
+
+#[test]
+fn reruns_if_status_is_in_user_defined_set() {
+    let dir = tempfile::tempdir().unwrap();
+    let sets = dir.path().join("constraint-sets");
+    std::fs::write(
+        &sets,
+        "# transient failures of mycmd\nmycmd-transient = 75 | @ssh-transient\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r=@mycmd-transient")
+        .arg("--max-tries=2")
+        .args(shell_command("echo run&& exit 255"))
+        .env("PRCS_CONSTRAINT_SETS", &sets)
+        .output()
+        .unwrap();
+    assert_eq!(from_utf8(&output.stdout).unwrap().lines().count(), 2);
 }
@@ -0,0 +1,117 @@
+#![cfg(unix)]
+
+mod utils;
+
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::thread::sleep;
+use std::time::Duration;
+use utils::shell_command;
+
+fn spawn_and_wait_until_ready(command: &mut Command) -> (Child, BufReader<ChildStdout>) {
+    let mut child = command.stdout(Stdio::piped()).spawn().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    assert_eq!(line, "ready\n");
+    (child, stdout)
+}
+
+fn send_signal(child: &Child, signal: &str) {
+    assert!(Command::new("kill")
+        .arg(format!("-{}", signal))
+        .arg(child.id().to_string())
+        .status()
+        .unwrap()
+        .success());
+}
+
+fn read_to_string(mut stdout: impl Read) -> String {
+    let mut output = String::new();
+    stdout.read_to_string(&mut output).unwrap();
+    output
+}
+
+const TRAPPING_COMMAND: &str = "\
+    trap 'echo USR1' USR1; \
+    trap 'echo TERM; exit 3' TERM; \
+    trap 'echo INT; exit 4' INT; \
+    echo ready; \
+    while true; do sleep 0.1; done";
+
+#[test]
+fn forwards_signals() {
+    let (mut child, stdout) = spawn_and_wait_until_ready(
+        Command::new(env!("CARGO_BIN_EXE_prcs")).args(shell_command(TRAPPING_COMMAND)),
+    );
+    send_signal(&child, "USR1");
+    sleep(Duration::from_millis(200));
+    send_signal(&child, "TERM");
+
+    assert_eq!(child.wait().unwrap().code().unwrap(), 3);
+    assert_eq!(read_to_string(stdout), "USR1\nTERM\n");
+}
+
+#[test]
+fn does_not_rerun_after_termination_signal() {
+    let (mut child, stdout) = spawn_and_wait_until_ready(
+        Command::new(env!("CARGO_BIN_EXE_prcs"))
+            .arg("-r")
+            .args(shell_command(TRAPPING_COMMAND)),
+    );
+    send_signal(&child, "TERM");
+
+    assert_eq!(child.wait().unwrap().code().unwrap(), 3);
+    assert_eq!(read_to_string(stdout), "TERM\n");
+}
+
+#[test]
+fn translates_signals() {
+    let (mut child, stdout) = spawn_and_wait_until_ready(
+        Command::new(env!("CARGO_BIN_EXE_prcs"))
+            .arg("--translate-signal=TERM:USR1")
+            .args(shell_command(TRAPPING_COMMAND)),
+    );
+    send_signal(&child, "TERM");
+    sleep(Duration::from_millis(200));
+    send_signal(&child, "INT");
+
+    assert_eq!(child.wait().unwrap().code().unwrap(), 4);
+    assert_eq!(read_to_string(stdout), "USR1\nINT\n");
+}
+
+#[test]
+fn reruns_after_signal_translated_from_termination_signal() {
+    let (mut child, stdout) = spawn_and_wait_until_ready(
+        Command::new(env!("CARGO_BIN_EXE_prcs"))
+            .args(["-r", "--translate-signal=HUP:USR1"])
+            .args(shell_command(
+                "\
+                if [ $PRCS_ATTEMPT = 1 ]; then \
+                    trap 'echo USR1; exit 1' USR1; \
+                    echo ready; \
+                    while true; do sleep 0.1; done; \
+                fi; \
+                echo rerun",
+            )),
+    );
+    send_signal(&child, "HUP");
+
+    assert_eq!(child.wait().unwrap().code().unwrap(), 0);
+    assert_eq!(read_to_string(stdout), "USR1\nrerun\n");
+}
+
+#[test]
+fn swallows_signals() {
+    let (mut child, stdout) = spawn_and_wait_until_ready(
+        Command::new(env!("CARGO_BIN_EXE_prcs"))
+            .arg("--swallow-signal=USR1")
+            .args(shell_command(TRAPPING_COMMAND)),
+    );
+    send_signal(&child, "USR1");
+    sleep(Duration::from_millis(200));
+    send_signal(&child, "TERM");
+
+    assert_eq!(child.wait().unwrap().code().unwrap(), 3);
+    assert_eq!(read_to_string(stdout), "TERM\n");
+}
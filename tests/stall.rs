@@ -13,7 +13,7 @@ const SLEEP_ATTEMPS: u32 = 100;
 fn does_not_stall_if_status_does_not_match() {
     let mut child = Command::new(env!("CARGO_BIN_EXE_prcs"))
         .arg("-s=>=100")
-        .args(&shell_command("exit 42"))
+        .args(shell_command("exit 42"))
         .spawn()
         .unwrap();
 
@@ -33,24 +33,123 @@ fn does_not_stall_if_status_does_not_match() {
 fn stalls_if_status_matches() {
     let mut child = Command::new(env!("CARGO_BIN_EXE_prcs"))
         .arg("-s=>=100")
-        .args(&shell_command("echo text && exit 123"))
+        .args(shell_command("echo text && exit 123"))
         .stdout(Stdio::piped())
         .spawn()
         .unwrap();
     let mut child_stdout = child.stdout.take().unwrap();
 
     let mut output = [0; 4];
+    child_stdout.read_exact(&mut output).unwrap();
+    assert_eq!(&output, b"text");
+
+    sleep(SLEEP_DURATION);
+    let stalled = child.try_wait().unwrap().is_none();
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(stalled)
+}
+
+#[test]
+fn stops_stalling_after_duration() {
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .args(["-s=42", "--stall-for=100ms"])
+        .args(shell_command("exit 42"))
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(42));
+}
+
+#[test]
+fn stalls_if_duration_is_too_long_to_represent() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .args(["-s=42", "--stall-for=500000000000y"])
+        .args(shell_command("echo text && exit 42"))
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut child_stdout = child.stdout.take().unwrap();
+
+    let mut output = [0; 4];
+    child_stdout.read_exact(&mut output).unwrap();
+
+    sleep(SLEEP_DURATION * 10);
+    let stalled = child.try_wait().unwrap().is_none();
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(stalled)
+}
+
+#[test]
+fn exits_with_stall_exit_status() {
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .args(["-s=42", "--stall-for=100ms", "--stall-exit-status=0"])
+        .args(shell_command("exit 42"))
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(0));
+}
+
+#[test]
+fn stops_stalling_once_file_exists() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("done");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-s")
+        .arg(format!("--stall-until-file={}", file.display()))
+        .args(shell_command("exit 0"))
+        .spawn()
+        .unwrap();
+
+    sleep(SLEEP_DURATION * 10);
+    let stalled = child.try_wait().unwrap().is_none();
+    std::fs::write(&file, "").unwrap();
+    for _ in 0..SLEEP_ATTEMPS {
+        if let Some(status) = child.try_wait().unwrap() {
+            assert!(stalled);
+            assert_eq!(status.code(), Some(0));
+            return;
+        }
+        sleep(SLEEP_DURATION);
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+    panic!(
+        "prcs did not exit in more than {:?}.",
+        SLEEP_ATTEMPS * SLEEP_DURATION
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn stops_stalling_on_termination_signal() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-s=42")
+        .args(shell_command("echo text && exit 42"))
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut child_stdout = child.stdout.take().unwrap();
+
+    let mut output = [0; 4];
+    child_stdout.read_exact(&mut output).unwrap();
+    sleep(SLEEP_DURATION * 10);
+    Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()
+        .unwrap();
+
     for _ in 0..SLEEP_ATTEMPS {
-        child_stdout.read(&mut output).unwrap();
-        if &output == b"text" {
-            sleep(SLEEP_DURATION);
-            assert!(child.try_wait().unwrap().is_none());
+        if let Some(status) = child.try_wait().unwrap() {
+            assert_eq!(status.code(), Some(42));
             return;
         }
         sleep(SLEEP_DURATION);
     }
+    child.kill().unwrap();
+    child.wait().unwrap();
     panic!(
-        "Did not observe child command exit in more than {:?}.",
+        "prcs did not exit in more than {:?}.",
         SLEEP_ATTEMPS * SLEEP_DURATION
     )
 }
@@ -0,0 +1,152 @@
+mod utils;
+
+use std::process::Command;
+use std::time::{Duration, Instant};
+use utils::shell_command;
+
+const LONG_RUNNING_COMMAND: &str = if cfg!(windows) {
+    "ping -n 30 127.0.0.1 >NUL"
+} else {
+    "exec sleep 30"
+};
+
+#[test]
+fn does_not_time_out_if_process_exits_in_time() {
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--timeout=10s")
+        .args(shell_command("exit 42"))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 42)
+}
+
+#[cfg(unix)]
+#[test]
+fn does_not_time_out_if_timeout_is_too_long_to_represent() {
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--timeout=500000000000y")
+        .args(shell_command("sleep 0.2; exit 42"))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 42)
+}
+
+#[test]
+fn times_out() {
+    let start = Instant::now();
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--timeout=100ms")
+        .args(shell_command(LONG_RUNNING_COMMAND))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 124);
+    assert!(start.elapsed() < Duration::from_secs(10))
+}
+
+#[test]
+fn reruns_after_timeout() {
+    let start = Instant::now();
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("-r=124")
+        .arg("--max-tries=2")
+        .arg("--timeout=100ms")
+        .args(shell_command(LONG_RUNNING_COMMAND))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 124);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert!(start.elapsed() < Duration::from_secs(10))
+}
+
+#[cfg(unix)]
+#[test]
+fn kills_after_timeout_signal_is_ignored() {
+    let start = Instant::now();
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--timeout=100ms")
+        .arg("--timeout-signal=USR1")
+        .arg("--kill-after=100ms")
+        .args(shell_command("trap '' USR1; exec sleep 30"))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 124);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert!(start.elapsed() < Duration::from_secs(10))
+}
+
+#[cfg(unix)]
+#[test]
+fn does_not_kill_if_kill_after_is_too_long_to_represent() {
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--timeout=100ms")
+        .arg("--kill-after=500000000000y")
+        .args(shell_command(LONG_RUNNING_COMMAND))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 124)
+}
+
+#[test]
+fn times_out_when_idle() {
+    let start = Instant::now();
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--idle-timeout=200ms")
+        .args(shell_command(if cfg!(windows) {
+            "echo text && ping -n 30 127.0.0.1 >NUL"
+        } else {
+            "echo text; exec sleep 30"
+        }))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 124);
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), "text");
+    assert!(start.elapsed() < Duration::from_secs(10))
+}
+
+#[cfg(unix)]
+#[test]
+fn does_not_time_out_if_idle_timeout_is_too_long_to_represent() {
+    let status = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--idle-timeout=500000000000y")
+        .args(shell_command("sleep 0.2; exit 42"))
+        .status()
+        .unwrap();
+    assert_eq!(status.code().unwrap(), 42)
+}
+
+#[cfg(unix)]
+#[test]
+fn does_not_time_out_while_printing() {
+    let start = Instant::now();
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--idle-timeout=300ms")
+        .args(shell_command(
+            "for i in 1 2 3 4 5 6; do echo $i; echo $i >&2; sleep 0.1; done; exit 42",
+        ))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 42);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "1\n2\n3\n4\n5\n6\n"
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "1\n2\n3\n4\n5\n6\n"
+    );
+    assert!(start.elapsed() >= Duration::from_millis(600))
+}
+
+#[test]
+fn rejects_out_of_range_timeout_signal() {
+    let output = Command::new(env!("CARGO_BIN_EXE_prcs"))
+        .arg("--timeout=100ms")
+        .arg("--timeout-signal=99999")
+        .args(shell_command("exit 0"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap(), 2);
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Signal number 99999 is out of range"))
+}
@@ -5,3 +5,42 @@ pub const fn shell_command(command: &'static str) -> [&'static str; 4] {
         ["--", "sh", "-c", command]
     }
 }
+
+/// Whether the process is still running, as opposed to not existing anymore or being a zombie.
+#[cfg(unix)]
+#[allow(dead_code)]
+pub fn running(pid: u32) -> bool {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "stat=", "-p", &pid.to_string()])
+        .output()
+        .unwrap();
+    let stat = String::from_utf8(output.stdout).unwrap();
+    !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+}
+
+/// Whether the process exists, even if only as a zombie.
+#[cfg(unix)]
+#[allow(dead_code)]
+pub fn exists(pid: u32) -> bool {
+    std::process::Command::new("ps")
+        .args(["-o", "stat=", "-p", &pid.to_string()])
+        .output()
+        .unwrap()
+        .status
+        .success()
+}
+
+/// Process ID of the parent of the process, if it exists.
+#[cfg(unix)]
+#[allow(dead_code)]
+pub fn parent(pid: u32) -> Option<u32> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "ppid=", "-p", &pid.to_string()])
+        .output()
+        .unwrap();
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .trim()
+        .parse()
+        .ok()
+}